@@ -0,0 +1,318 @@
+use std::io;
+use std::net::SocketAddr;
+use std::sync::mpsc::channel;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::thread;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tiny_http::{Header, Method, Request, Response, Server};
+use tungstenite::handshake::derive_accept_key;
+use tungstenite::protocol::Role;
+use tungstenite::{Message, WebSocket};
+
+pub mod lobby;
+
+use lobby::{ApiError, Lobby};
+
+/*
+Hosts games for playing over the network.  Everything is JSON.
+
+  GET  /games                       the games and who has joined them
+  POST /games                       {"name", "variant", "players", "seed"}, the creator takes seat 1
+  POST /games/{id}/join             {"name"}
+  GET  /games/{id}?token=...        the game as that player sees it, a spectator without a token
+  POST /games/{id}/play             {"token", "move": "8D RETAINS"}
+  POST /games/{id}/exchange         {"token", "tiles": "AB?"}
+  POST /games/{id}/pass             {"token"}
+  GET  /games/{id}/ws?token=...     a websocket sent the game state whenever it changes
+
+Joining gives the token for the seat.  Only its holder can move for that seat, and the states
+sent to them are the only ones with that rack on.
+*/
+
+fn json_header() -> Header {
+    Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..]).unwrap()
+}
+
+fn respond(request: Request, result: Result<impl Serialize, ApiError>) {
+    let (status, body) = match result {
+        Ok(value) => (200, serde_json::to_string(&value).unwrap()),
+        Err(e) => (
+            e.status,
+            serde_json::json!({ "error": e.message }).to_string(),
+        ),
+    };
+    let response = Response::from_string(body)
+        .with_status_code(status)
+        .with_header(json_header());
+    // the client may have gone
+    let _ = request.respond(response);
+}
+
+fn read_body<T: DeserializeOwned>(request: &mut Request) -> Result<T, ApiError> {
+    let mut body = String::new();
+    request
+        .as_reader()
+        .read_to_string(&mut body)
+        .map_err(|e| ApiError::bad_request(e.to_string()))?;
+    serde_json::from_str(&body).map_err(|e| ApiError::bad_request(e.to_string()))
+}
+
+// the path split into its parts, and the token if there is one
+fn parse_url(url: &str) -> (Vec<&str>, Option<&str>) {
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+    let token = query
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("token="));
+    let parts = path.split('/').filter(|part| !part.is_empty()).collect();
+    (parts, token)
+}
+
+fn parse_id(id: &str) -> Result<u64, ApiError> {
+    id.parse()
+        .map_err(|_| ApiError::not_found(format!("There is no game {}", id)))
+}
+
+/*
+A request which panicked part way through leaves the lobby locked as poisoned.  The games in it
+are still there, so the server carries on with them rather than every request after panicking too.
+*/
+fn lock(lobby: &Mutex<Lobby>) -> MutexGuard<'_, Lobby> {
+    lobby.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn handle(mut request: Request, lobby: Arc<Mutex<Lobby>>) {
+    let url = request.url().to_string();
+    let (parts, token) = parse_url(&url);
+    let method = request.method().clone();
+    let id = match parts[..] {
+        ["games", id, ..] => parse_id(id),
+        _ => Ok(0),
+    };
+    let id = match id {
+        Ok(id) => id,
+        Err(e) => return respond(request, Err::<(), _>(e)),
+    };
+    match (&method, &parts[..]) {
+        (Method::Get, ["games"]) => {
+            let games = lock(&lobby).games();
+            respond(request, Ok(games))
+        }
+        (Method::Post, ["games"]) => {
+            let result = read_body(&mut request).and_then(|r| lock(&lobby).create(r));
+            respond(request, result)
+        }
+        (Method::Get, ["games", _]) => {
+            let result = lock(&lobby).state(id, token);
+            respond(request, result)
+        }
+        (Method::Post, ["games", _, "join"]) => {
+            let result = read_body(&mut request).and_then(|r| lock(&lobby).join(id, r));
+            respond(request, result)
+        }
+        (Method::Post, ["games", _, "play"]) => {
+            let result = read_body(&mut request).and_then(|r| lock(&lobby).play(id, r));
+            respond(request, result)
+        }
+        (Method::Post, ["games", _, "exchange"]) => {
+            let result = read_body(&mut request).and_then(|r| lock(&lobby).exchange(id, r));
+            respond(request, result)
+        }
+        (Method::Post, ["games", _, "pass"]) => {
+            let result = read_body(&mut request).and_then(|r| lock(&lobby).pass(id, r));
+            respond(request, result)
+        }
+        (Method::Get, ["games", _, "ws"]) => {
+            let token = token.map(|token| token.to_string());
+            web_socket(request, lobby, id, token)
+        }
+        _ => respond(
+            request,
+            Err::<(), _>(ApiError::not_found(format!(
+                "No such request {} {}",
+                method, url
+            ))),
+        ),
+    }
+}
+
+/*
+Upgrades the request to a websocket, and passes on the game states from the lobby until the
+client goes away.  Nothing is read from the client, moves are made with the other requests.
+*/
+fn web_socket(request: Request, lobby: Arc<Mutex<Lobby>>, id: u64, token: Option<String>) {
+    let key = request
+        .headers()
+        .iter()
+        .find(|header| header.field.equiv("Sec-WebSocket-Key"))
+        .map(|header| header.value.to_string());
+    let Some(key) = key else {
+        return respond(
+            request,
+            Err::<(), _>(ApiError::bad_request("Not a websocket request")),
+        );
+    };
+    let (sender, receiver) = channel();
+    if let Err(e) = lock(&lobby).subscribe(id, token.as_deref(), sender) {
+        return respond(request, Err::<(), _>(e));
+    }
+    let response = Response::empty(101)
+        .with_header(Header::from_bytes(&b"Upgrade"[..], &b"websocket"[..]).unwrap())
+        .with_header(
+            Header::from_bytes(
+                &b"Sec-WebSocket-Accept"[..],
+                derive_accept_key(key.as_bytes()).as_bytes(),
+            )
+            .unwrap(),
+        );
+    let stream = request.upgrade("websocket", response);
+    let mut socket = WebSocket::from_raw_socket(stream, Role::Server, None);
+    for state in receiver {
+        if socket.send(Message::text(state)).is_err() {
+            break;
+        }
+    }
+}
+
+// each request is handled on its own thread, so websockets can stay open
+pub fn serve(server: Server, lobby: Arc<Mutex<Lobby>>) {
+    for request in server.incoming_requests() {
+        let lobby = lobby.clone();
+        thread::spawn(move || handle(request, lobby));
+    }
+}
+
+// starts serving in the background and gives the address it's listening on
+pub fn start(address: &str) -> io::Result<SocketAddr> {
+    let server = Server::http(address).map_err(io::Error::other)?;
+    let address = server
+        .server_addr()
+        .to_ip()
+        .ok_or_else(|| io::Error::other("Not listening on an IP address"))?;
+    thread::spawn(move || serve(server, Arc::new(Mutex::new(Lobby::new()))));
+    Ok(address)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::TcpStream;
+    use std::time::{Duration, Instant};
+
+    use serde_json::{json, Value};
+
+    use scrabble::board::SCRABBLE_VARIANT_OFFICIAL;
+    use scrabble::gcg::write_play;
+    use scrabble::notation::read_position;
+    use scrabble::word_list::WordCheck;
+    use tungstenite::stream::MaybeTlsStream;
+
+    use super::*;
+    use lobby::{GameState, Joined};
+
+    fn post(url: &str, body: Value) -> Result<Value, u16> {
+        match ureq::post(url).send_json(body) {
+            Ok(response) => Ok(response.into_json().unwrap()),
+            Err(ureq::Error::Status(status, _)) => Err(status),
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    fn next_state(socket: &mut WebSocket<MaybeTlsStream<TcpStream>>) -> GameState {
+        let message = socket.read().unwrap();
+        serde_json::from_str(message.to_text().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_networked_game() {
+        let address = start("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/games", address);
+        let first: Joined =
+            serde_json::from_value(post(&url, json!({"name": "Ann", "seed": 7})).unwrap()).unwrap();
+        let game_url = format!("{}/{}", url, first.game_id);
+        let second: Joined = serde_json::from_value(
+            post(&format!("{}/join", game_url), json!({"name": "Bob"})).unwrap(),
+        )
+        .unwrap();
+        assert_eq!((first.player, second.player), (0, 1));
+        assert_eq!(
+            post(&format!("{}/join", game_url), json!({"name": "Cat"})),
+            Err(403)
+        );
+
+        let ws_url = format!("ws://{}/games/{}/ws?token=", address, first.game_id);
+        let (mut ann, _) = tungstenite::connect(format!("{}{}", ws_url, first.token)).unwrap();
+        let (mut bob, _) = tungstenite::connect(format!("{}{}", ws_url, second.token)).unwrap();
+        // a state that never comes fails the test rather than hanging it
+        for socket in [&mut ann, &mut bob] {
+            if let MaybeTlsStream::Plain(stream) = socket.get_mut() {
+                stream
+                    .set_read_timeout(Some(Duration::from_secs(60)))
+                    .unwrap();
+            }
+        }
+        let state = next_state(&mut ann);
+        assert!(state.started);
+        assert_eq!(state.you, Some(0));
+        let rack = state.rack.clone().unwrap();
+        let state = next_state(&mut bob);
+        assert_eq!(state.you, Some(1));
+        assert_ne!(state.rack, None);
+        assert!(!serde_json::to_string(&state).unwrap().contains(&rack));
+
+        // out of turn, then something not on the rack
+        let pass = format!("{}/pass", game_url);
+        assert_eq!(post(&pass, json!({"token": second.token})), Err(403));
+        assert_eq!(post(&pass, json!({"token": "nonsense"})), Err(403));
+        let play = format!("{}/play", game_url);
+        assert_eq!(
+            post(&play, json!({"token": first.token, "move": "8H ZZZ"})),
+            Err(400)
+        );
+
+        let position = format!("{} {}/- 0/0 1", state.board, rack);
+        let game = read_position(&position, &SCRABBLE_VARIANT_OFFICIAL, WordCheck::Exact).unwrap();
+        let best = game
+            .legal_moves(Some(Instant::now() + Duration::from_secs(5)))
+            .into_iter()
+            .max_by_key(|m| m.score)
+            .unwrap();
+        let play_move = write_play(&game.board, &best);
+        let state: GameState = serde_json::from_value(
+            post(&play, json!({"token": first.token, "move": play_move})).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(state.players[0].score, best.score);
+        assert_eq!(state.last_move_tiles.len(), best.tiles.len());
+
+        let state = next_state(&mut bob);
+        assert_eq!(state.current_player, 1);
+        assert_eq!(state.players[0].score, best.score);
+        assert!(next_state(&mut ann)
+            .last_move
+            .unwrap()
+            .starts_with("Ann played"));
+
+        // a silly exchange is refused, and the server carries on
+        let exchange = format!("{}/exchange", game_url);
+        assert_eq!(
+            post(
+                &exchange,
+                json!({"token": second.token, "tiles": "A".repeat(300)})
+            ),
+            Err(400)
+        );
+        let tiles = &state.rack.unwrap()[..2];
+        post(&exchange, json!({"token": second.token, "tiles": tiles})).unwrap();
+        let state = next_state(&mut ann);
+        assert_eq!(state.last_move.unwrap(), "Bob exchanged 2 tiles");
+        assert_eq!(state.current_player, 0);
+
+        let games = ureq::get(&url)
+            .call()
+            .unwrap()
+            .into_json::<Value>()
+            .unwrap();
+        assert_eq!(games[0]["players"], json!(["Ann", "Bob"]));
+    }
+}
@@ -0,0 +1,396 @@
+use std::collections::HashMap;
+use std::sync::mpsc::Sender;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use scrabble::notation::{read_tiles, write_board};
+use scrabble::protocol::{read_action, VariantName};
+use scrabble::strategy::Action;
+use scrabble::{Game, GameMoveRecordDetail, Player, PlayerType, TScore};
+
+/*
+The games being hosted.  Each seat is taken by a player who is given a token, which they send
+with every request so only they can move for that seat and see that rack.
+*/
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiError {
+    pub status: u16,
+    pub message: String,
+}
+
+impl ApiError {
+    pub fn bad_request(message: impl Into<String>) -> Self {
+        Self {
+            status: 400,
+            message: message.into(),
+        }
+    }
+
+    pub fn forbidden(message: impl Into<String>) -> Self {
+        Self {
+            status: 403,
+            message: message.into(),
+        }
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self {
+            status: 404,
+            message: message.into(),
+        }
+    }
+}
+
+fn two_players() -> usize {
+    2
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct NewGameRequest {
+    pub name: String,
+    #[serde(default)]
+    pub variant: VariantName,
+    #[serde(default = "two_players")]
+    pub players: usize,
+    // fixes the tiles drawn
+    pub seed: Option<u64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct JoinRequest {
+    pub name: String,
+}
+
+// a play is written as in GCG files, e.g. 8D RETAINS, see scrabble::protocol
+#[derive(Debug, Clone, Deserialize)]
+pub struct PlayRequest {
+    pub token: String,
+    #[serde(rename = "move")]
+    pub play: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExchangeRequest {
+    pub token: String,
+    // '?' for a blank
+    pub tiles: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PassRequest {
+    pub token: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Joined {
+    pub game_id: u64,
+    pub player: usize,
+    pub token: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GameSummary {
+    pub id: u64,
+    pub variant: VariantName,
+    // None for a seat nobody has taken yet
+    pub players: Vec<Option<String>>,
+    pub started: bool,
+    pub is_over: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlayerState {
+    pub name: Option<String>,
+    pub score: TScore,
+    pub tiles: u8,
+}
+
+// a game as one player sees it, the other racks are only counted
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GameState {
+    pub id: u64,
+    pub variant: VariantName,
+    // see scrabble::notation::write_board
+    pub board: String,
+    // the squares filled by the last move, as [column, row]
+    pub last_move_tiles: Vec<[u8; 2]>,
+    pub last_move: Option<String>,
+    pub players: Vec<PlayerState>,
+    pub current_player: usize,
+    // None for a spectator
+    pub you: Option<usize>,
+    pub rack: Option<String>,
+    pub bag: u8,
+    pub started: bool,
+    pub is_over: bool,
+    pub winner: Option<usize>,
+}
+
+#[derive(Debug, Clone)]
+struct Seat {
+    name: String,
+    token: String,
+}
+
+// a websocket client, sent the state whenever the game changes
+#[derive(Debug)]
+struct Subscriber {
+    player: Option<usize>,
+    sender: Sender<String>,
+}
+
+#[derive(Debug)]
+struct HostedGame {
+    game: Game,
+    variant: VariantName,
+    seats: Vec<Option<Seat>>,
+    subscribers: Vec<Subscriber>,
+}
+
+impl HostedGame {
+    fn started(&self) -> bool {
+        self.seats.iter().all(|seat| seat.is_some())
+    }
+
+    fn seat(&self, token: &str) -> Result<usize, ApiError> {
+        self.seats
+            .iter()
+            .position(|seat| seat.as_ref().is_some_and(|seat| seat.token == token))
+            .ok_or_else(|| ApiError::forbidden("Unknown token"))
+    }
+
+    fn last_move(&self) -> Option<String> {
+        let record = self.game.moves.last()?;
+        Some(match &record.detail {
+            GameMoveRecordDetail::Move { word, score, .. } => {
+                format!("{} played {} for {}", record.player_name, word, score)
+            }
+            // which tiles went back is only known to the player
+            GameMoveRecordDetail::Exchange { tiles } => {
+                format!("{} exchanged {} tiles", record.player_name, tiles.len())
+            }
+            GameMoveRecordDetail::Pass => format!("{} passed", record.player_name),
+        })
+    }
+
+    fn state(&self, id: u64, you: Option<usize>) -> GameState {
+        let game = &self.game;
+        GameState {
+            id,
+            variant: self.variant,
+            board: write_board(&game.board),
+            last_move_tiles: game
+                .board
+                .last_move_positions()
+                .iter()
+                .map(|pos| [pos.x, pos.y])
+                .collect(),
+            last_move: self.last_move(),
+            players: (0..game.number_of_players)
+                .map(|i| PlayerState {
+                    name: self.seats[i].as_ref().map(|seat| seat.name.clone()),
+                    score: game.player[i].score,
+                    tiles: game.player[i].rack.count(),
+                })
+                .collect(),
+            current_player: game.current_player,
+            you,
+            rack: you.map(|i| game.player[i].rack.to_string()),
+            bag: game.bag.count(),
+            started: self.started(),
+            is_over: game.is_over,
+            winner: game.winner,
+        }
+    }
+
+    // subscribers which have gone away are dropped
+    fn broadcast(&mut self, id: u64) {
+        let states: Vec<(usize, String)> = self
+            .subscribers
+            .iter()
+            .enumerate()
+            .map(|(i, s)| (i, serde_json::to_string(&self.state(id, s.player)).unwrap()))
+            .collect();
+        let mut gone = vec![];
+        for (i, state) in states {
+            if self.subscribers[i].sender.send(state).is_err() {
+                gone.push(i);
+            }
+        }
+        for i in gone.into_iter().rev() {
+            self.subscribers.remove(i);
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Lobby {
+    games: HashMap<u64, HostedGame>,
+    next_id: u64,
+}
+
+fn new_token() -> String {
+    format!("{:016x}", rand::thread_rng().gen::<u64>())
+}
+
+impl Lobby {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn hosted(&mut self, id: u64) -> Result<&mut HostedGame, ApiError> {
+        self.games
+            .get_mut(&id)
+            .ok_or_else(|| ApiError::not_found(format!("There is no game {}", id)))
+    }
+
+    pub fn games(&self) -> Vec<GameSummary> {
+        let mut games: Vec<GameSummary> = self
+            .games
+            .iter()
+            .map(|(&id, hosted)| GameSummary {
+                id,
+                variant: hosted.variant,
+                players: hosted
+                    .seats
+                    .iter()
+                    .map(|seat| seat.as_ref().map(|seat| seat.name.clone()))
+                    .collect(),
+                started: hosted.started(),
+                is_over: hosted.game.is_over,
+            })
+            .collect();
+        games.sort_by_key(|game| game.id);
+        games
+    }
+
+    // the player creating the game takes the first seat
+    pub fn create(&mut self, request: NewGameRequest) -> Result<Joined, ApiError> {
+        if !(2..=4).contains(&request.players) {
+            return Err(ApiError::bad_request(
+                "There must be between 2 and 4 players",
+            ));
+        }
+        let mut game = Game::new(
+            request.variant.variant(),
+            request.players,
+            std::array::from_fn(|_| Player::new(PlayerType::Human)),
+            (0..request.players)
+                .map(|i| format!("Player {}", i + 1))
+                .collect(),
+        );
+        if let Some(seed) = request.seed {
+            game.restart_with_seed(seed);
+        }
+        self.next_id += 1;
+        let id = self.next_id;
+        self.games.insert(
+            id,
+            HostedGame {
+                game,
+                variant: request.variant,
+                seats: vec![None; request.players],
+                subscribers: vec![],
+            },
+        );
+        self.join(id, JoinRequest { name: request.name })
+    }
+
+    pub fn join(&mut self, id: u64, request: JoinRequest) -> Result<Joined, ApiError> {
+        let name = request.name.trim().to_string();
+        if name.is_empty() {
+            return Err(ApiError::bad_request("A name is needed"));
+        }
+        let hosted = self.hosted(id)?;
+        let player = hosted
+            .seats
+            .iter()
+            .position(|seat| seat.is_none())
+            .ok_or_else(|| ApiError::forbidden("The game is full"))?;
+        let token = new_token();
+        hosted.seats[player] = Some(Seat {
+            name: name.clone(),
+            token: token.clone(),
+        });
+        hosted.game.player_name[player] = name;
+        hosted.broadcast(id);
+        Ok(Joined {
+            game_id: id,
+            player,
+            token,
+        })
+    }
+
+    // without a token it is a spectator's view, with no rack
+    pub fn state(&mut self, id: u64, token: Option<&str>) -> Result<GameState, ApiError> {
+        let hosted = self.hosted(id)?;
+        let you = token.map(|token| hosted.seat(token)).transpose()?;
+        Ok(hosted.state(id, you))
+    }
+
+    // the current state is sent straight away, then again after every change
+    pub fn subscribe(
+        &mut self,
+        id: u64,
+        token: Option<&str>,
+        sender: Sender<String>,
+    ) -> Result<(), ApiError> {
+        let hosted = self.hosted(id)?;
+        let player = token.map(|token| hosted.seat(token)).transpose()?;
+        let state = serde_json::to_string(&hosted.state(id, player)).unwrap();
+        if sender.send(state).is_ok() {
+            hosted.subscribers.push(Subscriber { player, sender });
+        }
+        Ok(())
+    }
+
+    fn take_turn(&mut self, id: u64, token: &str, action: &Action) -> Result<GameState, ApiError> {
+        let hosted = self.hosted(id)?;
+        let player = hosted.seat(token)?;
+        if !hosted.started() {
+            return Err(ApiError::forbidden("Waiting for players to join"));
+        }
+        if hosted.game.is_over {
+            return Err(ApiError::forbidden("The game is over"));
+        }
+        if hosted.game.current_player != player {
+            return Err(ApiError::forbidden("It isn't your turn"));
+        }
+        hosted
+            .game
+            .apply_action(action)
+            .map_err(|e| ApiError::bad_request(format!("{:?}", e)))?;
+        hosted.broadcast(id);
+        Ok(hosted.state(id, Some(player)))
+    }
+
+    pub fn play(&mut self, id: u64, request: PlayRequest) -> Result<GameState, ApiError> {
+        let board = &self.hosted(id)?.game.board;
+        let action = match read_action(board, &request.play).map_err(ApiError::bad_request)? {
+            action @ Action::Play(_) => action,
+            _ => {
+                return Err(ApiError::bad_request(format!(
+                    "{} isn't a play",
+                    request.play
+                )))
+            }
+        };
+        self.take_turn(id, &request.token, &action)
+    }
+
+    pub fn exchange(&mut self, id: u64, request: ExchangeRequest) -> Result<GameState, ApiError> {
+        if request.tiles.chars().count() > 7 {
+            return Err(ApiError::bad_request(
+                "No more than 7 tiles can be exchanged",
+            ));
+        }
+        let tiles = read_tiles(&request.tiles.to_uppercase()).map_err(ApiError::bad_request)?;
+        self.take_turn(id, &request.token, &Action::Exchange(tiles.into()))
+    }
+
+    pub fn pass(&mut self, id: u64, request: PassRequest) -> Result<GameState, ApiError> {
+        self.take_turn(id, &request.token, &Action::Pass)
+    }
+}
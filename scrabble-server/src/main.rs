@@ -0,0 +1,30 @@
+use std::process::ExitCode;
+use std::sync::{Arc, Mutex};
+
+use clap::Parser;
+use tiny_http::Server;
+
+use scrabble_server::lobby::Lobby;
+use scrabble_server::serve;
+
+#[derive(Debug, Parser)]
+#[command(about = "Host Scrabble games for playing over the network")]
+struct Args {
+    /// The address to listen on, 0.0.0.0 lets other machines connect
+    #[arg(long, default_value = "0.0.0.0:8080")]
+    address: String,
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+    let server = match Server::http(&args.address) {
+        Ok(server) => server,
+        Err(e) => {
+            eprintln!("Could not listen on {}: {}", args.address, e);
+            return ExitCode::FAILURE;
+        }
+    };
+    println!("Listening on {}", args.address);
+    serve(server, Arc::new(Mutex::new(Lobby::new())));
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,149 @@
+use futures_channel::oneshot;
+use scrabble::Game;
+use scrabble::bot::BotConfig;
+use scrabble::strategy::Action;
+
+/*
+The computer thinks away from the UI so the page keeps responding.  On the desktop that is a
+thread of its own.  In the browser it is a web worker running this same program, which is sent
+the position as the computer sees it, in the notation of scrabble::protocol, and sends back the
+protocol's answer to go.
+*/
+
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn computer_action(game: Game, bot: BotConfig) -> Action {
+    use scrabble::strategy::Strategy;
+
+    let (sender, receiver) = oneshot::channel();
+    std::thread::spawn(move || {
+        let _ = sender.send(bot.choose_action(&game.view()));
+    });
+    receiver.await.unwrap_or(Action::Pass)
+}
+
+#[cfg(target_arch = "wasm32")]
+pub use worker::computer_action;
+
+#[cfg(target_arch = "wasm32")]
+mod worker {
+    use std::cell::RefCell;
+
+    use serde::{Deserialize, Serialize};
+    use wasm_bindgen::JsCast;
+    use wasm_bindgen::prelude::*;
+    use web_sys::{Blob, BlobPropertyBag, MessageEvent, Url, Worker, WorkerOptions, WorkerType};
+
+    use scrabble::notation::write_view_position;
+    use scrabble::protocol::{Engine, Request, Response, VariantName, read_action};
+
+    use super::*;
+
+    #[derive(Serialize, Deserialize)]
+    struct WorkerRequest {
+        bot: BotConfig,
+        variant: VariantName,
+        position: String,
+    }
+
+    /*
+    The worker loads the wasm-bindgen glue dx builds for the app, at /wasm/scrabble-ui.js, and
+    answers each message with think.  It is made from a blob so it doesn't go through the asset
+    pipeline.
+    */
+    const WORKER_SCRIPT: &str = r#"
+const ready = import("GLUE").then(async (glue) => {
+    await glue.default();
+    return glue.think;
+});
+onmessage = async (event) => {
+    const think = await ready;
+    postMessage(think(event.data));
+};
+"#;
+
+    thread_local! {
+        static WORKER: RefCell<Option<Worker>> = const { RefCell::new(None) };
+    }
+
+    // the worker's side, run by the script above
+    #[wasm_bindgen]
+    pub fn think(request: &str) -> String {
+        let response = match serde_json::from_str::<WorkerRequest>(request) {
+            Ok(request) => {
+                let mut engine = Engine::new(request.bot);
+                match engine.handle(Request::SetPosition {
+                    position: request.position,
+                    variant: Some(request.variant),
+                }) {
+                    error @ Response::Error { .. } => error,
+                    _ => engine.handle(Request::Go { time: None }),
+                }
+            }
+            Err(e) => Response::Error {
+                message: e.to_string(),
+            },
+        };
+        serde_json::to_string(&response).unwrap()
+    }
+
+    // the one worker, started the first time the computer has to move
+    fn worker() -> Result<Worker, JsValue> {
+        WORKER.with(|worker| {
+            if let Some(worker) = worker.borrow().as_ref() {
+                return Ok(worker.clone());
+            }
+            let window = web_sys::window().ok_or("No window")?;
+            let glue = format!("{}/wasm/scrabble-ui.js", window.location().origin()?);
+            let script = WORKER_SCRIPT.replace("GLUE", &glue);
+            let options = BlobPropertyBag::new();
+            options.set_type("text/javascript");
+            let blob = Blob::new_with_str_sequence_and_options(
+                &js_sys::Array::of1(&JsValue::from_str(&script)),
+                &options,
+            )?;
+            let options = WorkerOptions::new();
+            options.set_type(WorkerType::Module);
+            let new_worker =
+                Worker::new_with_options(&Url::create_object_url_with_blob(&blob)?, &options)?;
+            *worker.borrow_mut() = Some(new_worker.clone());
+            Ok(new_worker)
+        })
+    }
+
+    async fn ask_worker(game: &Game, bot: BotConfig) -> Result<Action, String> {
+        let request = WorkerRequest {
+            bot,
+            variant: VariantName::of(game.scrabble_variant()),
+            position: write_view_position(&game.view()),
+        };
+        let worker = worker().map_err(|e| format!("{:?}", e))?;
+        let (sender, receiver) = oneshot::channel::<String>();
+        let on_message = Closure::once(move |event: MessageEvent| {
+            let _ = sender.send(event.data().as_string().unwrap_or_default());
+        });
+        worker.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+        worker
+            .post_message(&JsValue::from_str(
+                &serde_json::to_string(&request).unwrap(),
+            ))
+            .map_err(|e| format!("{:?}", e))?;
+        let reply = receiver.await.map_err(|e| e.to_string());
+        worker.set_onmessage(None);
+        match serde_json::from_str::<Response>(&reply?).map_err(|e| e.to_string())? {
+            Response::BestMove { evaluation } => read_action(&game.board, &evaluation.play),
+            Response::Error { message } => Err(message),
+            response => Err(format!("Unexpected response {:?}", response)),
+        }
+    }
+
+    // anything going wrong with the worker is a pass
+    pub async fn computer_action(game: Game, bot: BotConfig) -> Action {
+        match ask_worker(&game, bot).await {
+            Ok(action) => action,
+            Err(e) => {
+                web_sys::console::error_1(&JsValue::from_str(&e));
+                Action::Pass
+            }
+        }
+    }
+}
@@ -1,2 +1,3 @@
 //! The components module contains all shared components for our app.
+pub mod computer;
 pub mod scrabble_board;
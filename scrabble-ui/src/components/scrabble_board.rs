@@ -1,329 +1,646 @@
 use dioxus::prelude::*;
+use rand::seq::SliceRandom;
+use scrabble::board::{
+    CellType, SCRABBLE_VARIANT_OFFICIAL, SCRABBLE_VARIANT_WORDFEUD, ScrabbleVariant,
+};
+use scrabble::bot::{BotConfig, BotLevel, Persona};
+use scrabble::placement::{self, placed_move, score_placements};
+use scrabble::pos::Position;
+use scrabble::statistics::game_statistics;
+use scrabble::strategy::Action;
+use scrabble::tiles::{ALPHABET, Letter, Tile, TileBag, TileList};
+use scrabble::{Direction, Game, GameMoveRecordDetail, Player, PlayerType};
 
-const BOARD_SIZE: usize = 15; // Full 15x15 Scrabble board
-const TILES: [char; 26] = [
-    'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', 'J', 'K', 'L', 'M', 'N', 'O', 'P', 'Q', 'R', 'S',
-    'T', 'U', 'V', 'W', 'X', 'Y', 'Z',
-];
+use super::computer::computer_action;
 
+// a tile put on the board this turn but not played yet, and where it came from on the rack
 #[derive(Clone, Copy, PartialEq)]
-enum SquareType {
-    Normal,
-    DoubleWord,
-    TripleWord,
-    DoubleLetter,
-    TripleLetter,
-    Center,
+struct Placement {
+    pos: Position,
+    tile: Tile,
+    rack_index: usize,
 }
 
-#[derive(Clone, Copy, PartialEq)]
-enum BoardLayout {
-    Traditional,
-    Wordfeud,
+// against the computer the human goes first, otherwise two people take turns at the same screen
+fn new_game(variant: &'static ScrabbleVariant, opponent: Option<BotConfig>) -> Game {
+    let (second, names) = match opponent {
+        Some(bot) => (
+            Player::new_computer(bot),
+            vec!["You".to_string(), format!("Computer ({})", bot)],
+        ),
+        None => (
+            Player::new(PlayerType::Human),
+            vec!["Player 1".to_string(), "Player 2".to_string()],
+        ),
+    };
+    let mut players = std::array::from_fn(|_| Player::new(PlayerType::Human));
+    players[1] = second;
+    Game::new(variant, 2, players, names)
 }
 
-fn get_square_type(row: usize, col: usize, layout: BoardLayout) -> SquareType {
-    // Center square
-    if row == 7 && col == 7 {
-        return SquareType::Center;
-    }
+// the tiles placed this turn as the engine takes them
+fn engine_placements(placements: &[Placement]) -> Vec<placement::Placement> {
+    placements
+        .iter()
+        .map(|p| placement::Placement::new(p.pos, p.tile))
+        .collect()
+}
 
-    match layout {
-        BoardLayout::Traditional => get_traditional_square_type(row, col),
-        BoardLayout::Wordfeud => get_wordfeud_square_type(row, col),
+// what the tiles on the board so far would score, word by word, or why they can't be played
+fn preview_placements(game: &Game, placements: &[Placement]) -> Option<Result<String, String>> {
+    if placements.is_empty() {
+        return None;
     }
+    Some(
+        score_placements(game, &engine_placements(placements))
+            .map(|scored| {
+                let mut parts = vec![format!(
+                    "{} {}",
+                    scored.main_word.word, scored.main_word.score
+                )];
+                for word in scored.cross_words.iter() {
+                    parts.push(format!("{} {}", word.word, word.score));
+                }
+                if scored.bingo_bonus > 0 {
+                    parts.push(format!("bingo {}", scored.bingo_bonus));
+                }
+                format!("{} = {} points", parts.join(" + "), scored.score())
+            })
+            .map_err(|e| e.to_string()),
+    )
 }
-fn get_traditional_square_type(row: usize, col: usize) -> SquareType {
-    // Traditional Scrabble layout (US/UK/International standard)
-
-    // Triple Word Score squares
-    const TRIPLE_WORD: [(usize, usize); 8] = [
-        (0, 0),
-        (0, 7),
-        (0, 14),
-        (7, 0),
-        (7, 14),
-        (14, 0),
-        (14, 7),
-        (14, 14),
-    ];
-    if TRIPLE_WORD.contains(&(row, col)) {
-        return SquareType::TripleWord;
+
+// what was played last, for the message under the board
+fn describe_last_move(game: &Game) -> Option<String> {
+    let record = game.last_move()?;
+    Some(match &record.detail {
+        GameMoveRecordDetail::Move { word, score, .. } => {
+            format!(
+                "{} played {} for {} points",
+                record.player_name, word, score
+            )
+        }
+        GameMoveRecordDetail::Exchange { tiles } => {
+            format!("{} exchanged {} tiles", record.player_name, tiles.len())
+        }
+        GameMoveRecordDetail::Pass => format!("{} passed", record.player_name),
+    })
+}
+
+fn get_square_style(cell_type: CellType, is_center: bool) -> &'static str {
+    if is_center {
+        return "width:30px;height:30px;border:1px solid #666;text-align:center;font-size:14px;cursor:pointer;background:#ffd700;color:#8b0000;font-weight:bold;";
+    }
+    match cell_type {
+        CellType::Blank => {
+            "width:30px;height:30px;border:1px solid #666;text-align:center;font-size:14px;cursor:pointer;background:#f5f5dc;"
+        }
+        CellType::DoubleWord => {
+            "width:30px;height:30px;border:1px solid #666;text-align:center;font-size:14px;cursor:pointer;background:#ffb6c1;color:#8b0000;font-weight:bold;"
+        }
+        CellType::TripleWord => {
+            "width:30px;height:30px;border:1px solid #666;text-align:center;font-size:14px;cursor:pointer;background:#ff4500;color:white;font-weight:bold;"
+        }
+        CellType::DoubleLetter => {
+            "width:30px;height:30px;border:1px solid #666;text-align:center;font-size:14px;cursor:pointer;background:#87ceeb;color:#000080;font-weight:bold;"
+        }
+        CellType::TripleLetter => {
+            "width:30px;height:30px;border:1px solid #666;text-align:center;font-size:14px;cursor:pointer;background:#0000ff;color:white;font-weight:bold;"
+        }
     }
+}
 
-    // Double Word Score squares
-    const DOUBLE_WORD: [(usize, usize); 16] = [
-        (1, 1),
-        (1, 13),
-        (2, 2),
-        (2, 12),
-        (3, 3),
-        (3, 11),
-        (4, 4),
-        (4, 10),
-        (10, 4),
-        (10, 10),
-        (11, 3),
-        (11, 11),
-        (12, 2),
-        (12, 12),
-        (13, 1),
-        (13, 13),
-    ];
-    if DOUBLE_WORD.contains(&(row, col)) {
-        return SquareType::DoubleWord;
+fn get_square_label(cell_type: CellType, is_center: bool) -> &'static str {
+    if is_center {
+        return "★";
     }
+    match cell_type {
+        CellType::Blank => "",
+        CellType::DoubleWord => "2W",
+        CellType::TripleWord => "3W",
+        CellType::DoubleLetter => "2L",
+        CellType::TripleLetter => "3L",
+    }
+}
 
-    // Triple Letter Score squares
-    const TRIPLE_LETTER: [(usize, usize); 12] = [
-        (1, 5),
-        (1, 9),
-        (5, 1),
-        (5, 5),
-        (5, 9),
-        (5, 13),
-        (9, 1),
-        (9, 5),
-        (9, 9),
-        (9, 13),
-        (13, 5),
-        (13, 9),
-    ];
-    if TRIPLE_LETTER.contains(&(row, col)) {
-        return SquareType::TripleLetter;
+fn get_choice_style(selected: bool) -> &'static str {
+    match selected {
+        true => {
+            "padding:10px 20px;font-size:16px;background:#4CAF50;color:white;border:none;border-radius:5px;cursor:pointer;"
+        }
+        false => {
+            "padding:10px 20px;font-size:16px;background:#f0f0f0;color:#333;border:1px solid #ccc;border-radius:5px;cursor:pointer;"
+        }
     }
+}
 
-    // Double Letter Score squares
-    const DOUBLE_LETTER: [(usize, usize); 24] = [
-        (0, 3),
-        (0, 11),
-        (2, 6),
-        (2, 8),
-        (3, 0),
-        (3, 7),
-        (3, 14),
-        (6, 2),
-        (6, 6),
-        (6, 8),
-        (6, 12),
-        (7, 3),
-        (7, 11),
-        (8, 2),
-        (8, 6),
-        (8, 8),
-        (8, 12),
-        (11, 0),
-        (11, 7),
-        (11, 14),
-        (12, 6),
-        (12, 8),
-        (14, 3),
-        (14, 11),
-    ];
-    if DOUBLE_LETTER.contains(&(row, col)) {
-        return SquareType::DoubleLetter;
+fn get_tile_style(placed: bool, last_move: bool) -> &'static str {
+    match (placed, last_move) {
+        (true, _) => {
+            "background:#ffe680;border:2px solid #b8860b;border-radius:3px;width:100%;height:100%;display:flex;align-items:center;justify-content:center;font-weight:bold;color:#2f4f4f;"
+        }
+        (false, true) => {
+            "background:#f4e4bc;border:2px solid #228b22;border-radius:3px;width:100%;height:100%;display:flex;align-items:center;justify-content:center;font-weight:bold;color:#2f4f4f;"
+        }
+        (false, false) => {
+            "background:#f4e4bc;border:1px solid #8b4513;border-radius:3px;width:100%;height:100%;display:flex;align-items:center;justify-content:center;font-weight:bold;color:#2f4f4f;"
+        }
     }
+}
 
-    SquareType::Normal
+// where a tile being dragged was picked up from
+#[derive(Clone, Copy, PartialEq)]
+enum Drag {
+    Rack(usize),
+    Board(Position),
 }
 
-fn get_wordfeud_square_type(row: usize, col: usize) -> SquareType {
-    // Triple Word Score squares - Wordfeud pattern
-    const TRIPLE_WORD: [(usize, usize); 8] = [
-        (0, 0),
-        (0, 7),
-        (0, 14),
-        (7, 0),
-        (7, 14),
-        (14, 0),
-        (14, 7),
-        (14, 14),
-    ];
-    if TRIPLE_WORD.contains(&(row, col)) {
-        return SquareType::TripleWord;
+/*
+The rack in the order the player has put it in.  The tiles still on it keep their places and
+the ones just drawn go on the end.
+*/
+fn arrange_rack(order: &[Tile], rack: TileBag) -> Vec<Tile> {
+    let mut left = rack;
+    let mut arranged = vec![];
+    for &tile in order {
+        let on_rack = match tile {
+            Tile::Letter(letter) => left.letters[letter.as_usize()] > 0,
+            Tile::Blank { .. } => left.blanks > 0,
+        };
+        if on_rack {
+            left.remove_tile(tile);
+            arranged.push(tile);
+        }
     }
+    arranged.extend(TileList::from(left).0);
+    arranged
+}
 
-    // Double Word Score squares - Wordfeud has different pattern
-    const DOUBLE_WORD: [(usize, usize); 17] = [
-        (1, 1),
-        (1, 13),
-        (2, 2),
-        (2, 12),
-        (3, 3),
-        (3, 11),
-        (4, 4),
-        (4, 10),
-        (7, 7),
-        (10, 4),
-        (10, 10),
-        (11, 3),
-        (11, 11),
-        (12, 2),
-        (12, 12),
-        (13, 1),
-        (13, 13),
-    ];
-    if DOUBLE_WORD.contains(&(row, col)) {
-        return SquareType::DoubleWord;
+// the next square along without a tile on it, where the next letter typed goes
+fn next_free_square(
+    game: &Game,
+    placements: &[Placement],
+    pos: Position,
+    direction: Direction,
+) -> Option<Position> {
+    let mut pos = pos.try_step_forward(direction)?;
+    while !game.board.get_cell_pos(pos).is_empty() || placements.iter().any(|p| p.pos == pos) {
+        pos = pos.try_step_forward(direction)?;
     }
+    Some(pos)
+}
 
-    // Triple Letter Score squares - Wordfeud pattern
-    const TRIPLE_LETTER: [(usize, usize); 12] = [
-        (1, 5),
-        (1, 9),
-        (5, 1),
-        (5, 5),
-        (5, 9),
-        (5, 13),
-        (9, 1),
-        (9, 5),
-        (9, 9),
-        (9, 13),
-        (13, 5),
-        (13, 9),
-    ];
-    if TRIPLE_LETTER.contains(&(row, col)) {
-        return SquareType::TripleLetter;
+fn get_rack_tile_style(selected: bool, exchanging: bool) -> &'static str {
+    match (selected, exchanging) {
+        (true, true) => {
+            "margin:2px;padding:8px 12px;font-size:16px;font-weight:bold;background:#f4e4bc;border:2px solid #c62828;border-radius:5px;cursor:pointer;color:#2f4f4f;min-width:40px;transform:translateY(-6px);"
+        }
+        (true, false) => {
+            "margin:2px;padding:8px 12px;font-size:16px;font-weight:bold;background:#ffe680;border:2px solid #b8860b;border-radius:5px;cursor:grab;color:#2f4f4f;min-width:40px;"
+        }
+        (false, _) => {
+            "margin:2px;padding:8px 12px;font-size:16px;font-weight:bold;background:#f4e4bc;border:2px solid #8b4513;border-radius:5px;cursor:grab;color:#2f4f4f;min-width:40px;"
+        }
     }
+}
 
-    // Double Letter Score squares - Wordfeud has more double letter squares
-    const DOUBLE_LETTER: [(usize, usize); 32] = [
-        (0, 3),
-        (0, 11),
-        (2, 6),
-        (2, 8),
-        (3, 0),
-        (3, 7),
-        (3, 14),
-        (6, 2),
-        (6, 6),
-        (6, 8),
-        (6, 12),
-        (7, 3),
-        (7, 11),
-        (8, 2),
-        (8, 6),
-        (8, 8),
-        (8, 12),
-        (11, 0),
-        (11, 7),
-        (11, 14),
-        (12, 6),
-        (12, 8),
-        (14, 3),
-        (14, 11),
-        // Additional Wordfeud double letter squares
-        (4, 6),
-        (4, 8),
-        (6, 4),
-        (6, 10),
-        (8, 4),
-        (8, 10),
-        (10, 6),
-        (10, 8),
-    ];
-    if DOUBLE_LETTER.contains(&(row, col)) {
-        return SquareType::DoubleLetter;
-    }
+#[component]
+pub fn scrabble_board() -> Element {
+    let default_opponent = Some(BotConfig::new(BotLevel::Intermediate, Persona::Standard));
+    let mut game = use_signal(|| new_game(&SCRABBLE_VARIANT_OFFICIAL, default_opponent));
+    let mut opponent = use_signal(|| default_opponent);
+    let mut variant_choice = use_signal(|| &SCRABBLE_VARIANT_OFFICIAL);
+    let mut opponent_choice = use_signal(|| Some(BotLevel::Intermediate));
+    // so a move the computer was still thinking about isn't played in a new game
+    let mut game_number = use_signal(|| 0usize);
+    let mut thinking = use_signal(|| false);
+    let mut placements = use_signal(Vec::<Placement>::new);
+    let mut selected_tile = use_signal(|| None::<usize>);
+    let mut message = use_signal(|| None::<String>);
+    // a blank put on a square, waiting to be told which letter it is
+    let mut blank_square = use_signal(|| None::<(Position, usize)>);
+    // the order each player has put their rack in
+    let mut rack_orders = use_signal(|| vec![Vec::<Tile>::new(); 4]);
+    let mut dragging = use_signal(|| None::<Drag>);
+    // the tiles picked to go back in the bag, while choosing them
+    let mut exchanging = use_signal(|| None::<Vec<usize>>);
+    // where the next letter typed goes, and which way the word is going
+    let mut cursor = use_signal(|| None::<(Position, Direction)>);
 
-    SquareType::Normal
-}
-fn get_square_style(square_type: SquareType) -> &'static str {
-    match square_type {
-        SquareType::Normal => {
-            "width:30px;height:30px;border:1px solid #666;text-align:center;font-size:14px;cursor:pointer;background:#f5f5dc;"
-        }
-        SquareType::DoubleWord => {
-            "width:30px;height:30px;border:1px solid #666;text-align:center;font-size:14px;cursor:pointer;background:#ffb6c1;color:#8b0000;font-weight:bold;"
+    let start_game = move |_| {
+        let bot = (*opponent_choice.read()).map(|level| BotConfig::new(level, Persona::Standard));
+        game.set(new_game(*variant_choice.read(), bot));
+        opponent.set(bot);
+        game_number += 1;
+        thinking.set(false);
+        placements.set(vec![]);
+        selected_tile.set(None);
+        blank_square.set(None);
+        rack_orders.set(vec![vec![]; 4]);
+        exchanging.set(None);
+        cursor.set(None);
+        message.set(None);
+    };
+
+    // whenever it is the computer's turn it goes off to think, and its move is played when it's back
+    use_effect(move || {
+        let game_state = game.read();
+        let is_computer = game_state.current_player().player_type == PlayerType::Computer;
+        let Some(bot) = *opponent.peek() else {
+            return;
+        };
+        if game_state.is_over || !is_computer || *thinking.peek() {
+            return;
         }
-        SquareType::TripleWord => {
-            "width:30px;height:30px;border:1px solid #666;text-align:center;font-size:14px;cursor:pointer;background:#ff4500;color:white;font-weight:bold;"
+        let position = game_state.clone();
+        let number = *game_number.peek();
+        drop(game_state);
+        thinking.set(true);
+        spawn(async move {
+            let action = computer_action(position, bot).await;
+            if *game_number.peek() != number {
+                return;
+            }
+            thinking.set(false);
+            {
+                let mut game = game.write();
+                if game.apply_action(&action).is_err() {
+                    game.pass();
+                }
+            }
+            message.set(describe_last_move(&game.read()));
+        });
+    });
+
+    // against the computer it's always your rack on show, otherwise the player whose turn it is
+    let rack_player = move || match *opponent.read() {
+        Some(_) => 0,
+        None => game.read().current_player,
+    };
+
+    // the rack on show, in the order its player has put it in
+    let rack_tiles = move || {
+        let player = rack_player();
+        arrange_rack(&rack_orders.read()[player], game.read().player[player].rack)
+    };
+
+    let mut recall = move || {
+        placements.set(vec![]);
+        selected_tile.set(None);
+        blank_square.set(None);
+        cursor.set(None);
+    };
+
+    // puts a tile from the rack on a square, a blank has to be given its letter first
+    let mut place_tile = move |pos: Position, rack_index: usize| {
+        let free = game.read().board.get_cell_pos(pos).is_empty()
+            && placements.read().iter().all(|p| p.pos != pos);
+        if !free {
+            return;
         }
-        SquareType::DoubleLetter => {
-            "width:30px;height:30px;border:1px solid #666;text-align:center;font-size:14px;cursor:pointer;background:#87ceeb;color:#000080;font-weight:bold;"
+        let tile = rack_tiles()[rack_index];
+        selected_tile.set(None);
+        if tile.letter().is_none() {
+            blank_square.set(Some((pos, rack_index)));
+            return;
         }
-        SquareType::TripleLetter => {
-            "width:30px;height:30px;border:1px solid #666;text-align:center;font-size:14px;cursor:pointer;background:#0000ff;color:white;font-weight:bold;"
+        placements.write().push(Placement {
+            pos,
+            tile,
+            rack_index,
+        });
+    };
+
+    /*
+    Puts the rack in a new order, given as where each tile was before.  The tiles on the board
+    are kept track of by where they were on the rack, so they are moved along with it.
+    */
+    let mut rearrange = move |new_order: Vec<usize>| {
+        let rack = rack_tiles();
+        let new_index = |i: usize| new_order.iter().position(|&j| j == i).unwrap();
+        let player = rack_player();
+        rack_orders.write()[player] = new_order.iter().map(|&i| rack[i]).collect();
+        for placement in placements.write().iter_mut() {
+            placement.rack_index = new_index(placement.rack_index);
         }
-        SquareType::Center => {
-            "width:30px;height:30px;border:1px solid #666;text-align:center;font-size:14px;cursor:pointer;background:#ffd700;color:#8b0000;font-weight:bold;"
+        let waiting = *blank_square.read();
+        blank_square.set(waiting.map(|(pos, i)| (pos, new_index(i))));
+        let chosen = exchanging.read().clone();
+        exchanging.set(chosen.map(|chosen| chosen.into_iter().map(new_index).collect()));
+        selected_tile.set(None);
+    };
+
+    // moves a tile along the rack to where another one is
+    let mut move_on_rack = move |from: usize, to: usize| {
+        let mut order: Vec<usize> = (0..rack_tiles().len()).collect();
+        let tile = order.remove(from);
+        order.insert(to.min(order.len()), tile);
+        rearrange(order);
+    };
+
+    let shuffle = move |_| {
+        let mut order: Vec<usize> = (0..rack_tiles().len()).collect();
+        order.shuffle(&mut rand::thread_rng());
+        rearrange(order);
+    };
+
+    let mut play = move || {
+        let result = placed_move(&game.read(), &engine_placements(&placements.read()));
+        let result =
+            result.and_then(|game_move| game.write().apply_action(&Action::Play(game_move)));
+        match result {
+            Ok(()) => {
+                recall();
+                message.set(describe_last_move(&game.read()));
+            }
+            Err(e) => message.set(Some(e.to_string())),
         }
-    }
-}
+    };
 
-fn get_square_label(square_type: SquareType) -> &'static str {
-    match square_type {
-        SquareType::Normal => "",
-        SquareType::DoubleWord => "2W",
-        SquareType::TripleWord => "3W",
-        SquareType::DoubleLetter => "2L",
-        SquareType::TripleLetter => "3L",
-        SquareType::Center => "★",
-    }
-}
+    let pass = move |_| {
+        recall();
+        exchanging.set(None);
+        game.write().pass();
+        message.set(describe_last_move(&game.read()));
+    };
 
-#[component]
-pub fn scrabble_board() -> Element {
-    let mut board = use_signal(|| vec![vec![' '; BOARD_SIZE]; BOARD_SIZE]);
-    let mut selected_tile = use_signal(|| None::<char>);
-    let mut current_layout = use_signal(|| BoardLayout::Traditional);
+    let exchange = move |_| {
+        let Some(chosen) = exchanging.read().clone() else {
+            recall();
+            exchanging.set(Some(vec![]));
+            return;
+        };
+        let rack = rack_tiles();
+        let tiles = TileList(chosen.iter().map(|&i| rack[i]).collect());
+        let result = game.write().exchange_tiles(&tiles);
+        match result {
+            Ok(()) => {
+                exchanging.set(None);
+                message.set(describe_last_move(&game.read()));
+            }
+            Err(e) => message.set(Some(e.to_string())),
+        }
+    };
 
-    let board_state = board.read();
+    /*
+    Typing a word: letters go down from the square clicked, using a blank when the letter isn't
+    on the rack.  The arrow keys choose across or down, backspace takes the last tile back up,
+    return plays and escape recalls everything.
+    */
+    let on_key = move |event: KeyboardEvent| {
+        let game_state = game.read().clone();
+        if game_state.is_over
+            || *thinking.read()
+            || game_state.current_player().player_type != PlayerType::Human
+            || exchanging.read().is_some()
+        {
+            return;
+        }
+        match event.key() {
+            Key::ArrowLeft | Key::ArrowRight => {
+                event.prevent_default();
+                let square = *cursor.read();
+                cursor.set(square.map(|(pos, _)| (pos, Direction::Horizontal)));
+            }
+            Key::ArrowUp | Key::ArrowDown => {
+                event.prevent_default();
+                let square = *cursor.read();
+                cursor.set(square.map(|(pos, _)| (pos, Direction::Vertical)));
+            }
+            Key::Backspace => {
+                let last = placements.write().pop();
+                if let Some(last) = last {
+                    let direction = cursor.read().map_or(Direction::Horizontal, |(_, d)| d);
+                    cursor.set(Some((last.pos, direction)));
+                }
+            }
+            Key::Enter => play(),
+            Key::Escape => recall(),
+            Key::Character(text) => {
+                let (Some((pos, direction)), Some(c)) = (*cursor.read(), text.chars().next())
+                else {
+                    return;
+                };
+                if text.chars().count() != 1 || !c.is_ascii_alphabetic() {
+                    return;
+                }
+                let letter = Letter::from(c.to_ascii_uppercase());
+                let rack = rack_tiles();
+                let placed = placements.read().clone();
+                let unused = |i: &usize| placed.iter().all(|p| p.rack_index != *i);
+                let from_rack = (0..rack.len())
+                    .filter(unused)
+                    .find(|&i| rack[i] == Tile::Letter(letter))
+                    .map(|i| (i, rack[i]))
+                    .or_else(|| {
+                        (0..rack.len())
+                            .filter(unused)
+                            .find(|&i| rack[i].is_blank())
+                            .map(|i| {
+                                let blank = Tile::Blank {
+                                    acting_as_letter: Some(letter),
+                                };
+                                (i, blank)
+                            })
+                    });
+                let Some((rack_index, tile)) = from_rack else {
+                    message.set(Some(format!("There is no {} on the rack", letter)));
+                    return;
+                };
+                placements.write().push(Placement {
+                    pos,
+                    tile,
+                    rack_index,
+                });
+                let next = next_free_square(&game_state, &placements.read(), pos, direction);
+                cursor.set(next.map(|next| (next, direction)));
+            }
+            _ => {}
+        }
+    };
+
+    let game_state = game.read();
+    let variant = game_state.scrabble_variant();
+    let current_player = game_state.current_player;
+    let is_thinking = *thinking.read();
+    let your_turn = !game_state.is_over
+        && !is_thinking
+        && game_state.current_player().player_type == PlayerType::Human;
+    let rack = rack_tiles();
+    let rack_player = rack_player();
+    let placed = placements.read().clone();
+    let pending_blank = *blank_square.read();
+    let chosen = exchanging.read().clone();
+    let typing = *cursor.read();
+    let center = Position::new(variant.width / 2, variant.height / 2);
+    let chosen_variant = *variant_choice.read();
+    let chosen_opponent = *opponent_choice.read();
+    let preview = match your_turn {
+        true => preview_placements(&game_state, &placed),
+        false => None,
+    };
+    let summary = game_state.is_over.then(|| game_statistics(&game_state));
 
     rsx! {
-        div { class: "scrabble-container",
-            h2 { "Full Size Scrabble Board (15x15)" }
-            // Layout selector
+        div {
+            class: "scrabble-container",
+            tabindex: "0",
+            style: "outline:none;",
+            onkeydown: on_key,
+            h2 { "Scrabble" }
             div {
                 class: "layout-selector",
                 style: "margin:20px auto;text-align:center;",
-                h3 { "Choose Board Layout:" }
+                h3 { "New game" }
                 div { style: "display:flex;justify-content:center;gap:10px;margin:10px;",
                     button {
-                        onclick: move |_| current_layout.set(BoardLayout::Traditional),
-                        style: if *current_layout.read() == BoardLayout::Traditional { "padding:10px 20px;font-size:16px;background:#4CAF50;color:white;border:none;border-radius:5px;cursor:pointer;" } else { "padding:10px 20px;font-size:16px;background:#f0f0f0;color:#333;border:1px solid #ccc;border-radius:5px;cursor:pointer;" },
+                        onclick: move |_| variant_choice.set(&SCRABBLE_VARIANT_OFFICIAL),
+                        style: get_choice_style(*chosen_variant == SCRABBLE_VARIANT_OFFICIAL),
                         "Traditional"
                     }
                     button {
-                        onclick: move |_| current_layout.set(BoardLayout::Wordfeud),
-                        style: if *current_layout.read() == BoardLayout::Wordfeud { "padding:10px 20px;font-size:16px;background:#4CAF50;color:white;border:none;border-radius:5px;cursor:pointer;" } else { "padding:10px 20px;font-size:16px;background:#f0f0f0;color:#333;border:1px solid #ccc;border-radius:5px;cursor:pointer;" },
+                        onclick: move |_| variant_choice.set(&SCRABBLE_VARIANT_WORDFEUD),
+                        style: get_choice_style(*chosen_variant == SCRABBLE_VARIANT_WORDFEUD),
                         "Wordfeud"
                     }
                 }
+                div { style: "display:flex;justify-content:center;gap:10px;margin:10px;",
+                    button {
+                        onclick: move |_| opponent_choice.set(None),
+                        style: get_choice_style(chosen_opponent.is_none()),
+                        "Two players"
+                    }
+                    {
+                        [BotLevel::Beginner, BotLevel::Intermediate, BotLevel::Expert]
+                            .into_iter()
+                            .map(|level| {
+                                rsx! {
+                                    button {
+                                        onclick: move |_| opponent_choice.set(Some(level)),
+                                        style: get_choice_style(chosen_opponent == Some(level)),
+                                        "{level} computer"
+                                    }
+                                }
+                            })
+                    }
+                }
+                button {
+                    onclick: start_game,
+                    style: "padding:10px 20px;font-size:16px;background:#2f4f4f;color:white;border:none;border-radius:5px;cursor:pointer;margin:10px;",
+                    "Start"
+                }
             }
+
+            div {
+                class: "scores",
+                style: "display:flex;justify-content:center;gap:30px;margin:10px;font-size:18px;",
+                {
+                    (0..game_state.number_of_players)
+                        .map(|i| {
+                            let name = game_state.player_name[i].clone();
+                            let score = game_state.player[i].score;
+                            rsx! {
+                                div { style: if i == current_player && !game_state.is_over { "font-weight:bold;border-bottom:3px solid #4CAF50;" } else { "" },
+                                    "{name}: {score}"
+                                }
+                            }
+                        })
+                }
+                div { style: "color:#666;", "Bag: {game_state.bag.count()}" }
+            }
+            if is_thinking {
+                div { style: "margin:10px auto;text-align:center;font-style:italic;color:#666;",
+                    "The computer is thinking..."
+                }
+            }
+
             div { class: "scrabble-board",
                 table { style: "border-collapse:collapse;margin:20px auto;",
                     tbody {
                         {
-                            board_state
-                                .iter()
-                                .enumerate()
-                                .map(|(row_idx, row)| {
+                            (0..variant.height)
+                                .map(|y| {
                                     rsx! {
                                         tr {
                                             {
-                                                row.iter()
-                                                    .enumerate()
-                                                    .map(|(col_idx, &cell)| {
-                                                        let layout = *current_layout.read();
-                                                        let square_type = get_square_type(row_idx, col_idx, layout);
-                                                        let square_style = get_square_style(square_type);
-                                                        let square_label = get_square_label(square_type);
+                                                (0..variant.width)
+                                                    .map(|x| {
+                                                        let pos = Position::new(x, y);
+                                                        let cell = game_state.board.get_cell_pos(pos);
+                                                        let is_center = pos == center;
+                                                        let square_style = get_square_style(cell.cell_type(), is_center);
+                                                        let square_label = match typing {
+                                                            Some((square, Direction::Horizontal)) if square == pos => "→",
+                                                            Some((square, Direction::Vertical)) if square == pos => "↓",
+                                                            _ => get_square_label(cell.cell_type(), is_center),
+                                                        };
+                                                        let outline = match typing {
+                                                            Some((square, _)) if square == pos => "outline:3px solid #228b22;outline-offset:-3px;",
+                                                            _ => "",
+                                                        };
+                                                        let placement = placed.iter().find(|p| p.pos == pos).copied();
+                                                        let waiting = pending_blank.filter(|(square, _)| *square == pos).map(|_| Tile::Blank {
+                                                            acting_as_letter: None,
+                                                        });
+                                                        let tile = placement.map(|p| p.tile).or(waiting).or(cell.tile());
+                                                        let tile_style = get_tile_style(placement.is_some() || waiting.is_some(), cell.is_last_move());
                                                         rsx! {
                                                             td {
                                                                 onclick: move |_| {
-                                                                    let tile_opt = selected_tile.read().clone();
-                                                                    if let Some(tile) = tile_opt {
-                                                                        let mut new_board = board.read().clone();
-                                                                        new_board[row_idx][col_idx] = tile;
-                                                                        board.set(new_board);
-                                                                        selected_tile.set(None);
+                                                                    if !your_turn || exchanging.read().is_some() {
+                                                                        return;
+                                                                    }
+                                                                    if placement.is_some() {
+                                                                        // picks the tile back up
+                                                                        placements.write().retain(|p| p.pos != pos);
+                                                                        return;
+                                                                    }
+                                                                    match *selected_tile.read() {
+                                                                        Some(rack_index) => place_tile(pos, rack_index),
+                                                                        // with no tile picked, clicking a square is where typing starts, clicking again turns it
+                                                                        None if game.read().board.get_cell_pos(pos).is_empty() => {
+                                                                            let direction = match *cursor.read() {
+                                                                                Some((square, direction)) if square == pos => -direction,
+                                                                                Some((_, direction)) => direction,
+                                                                                None => Direction::Horizontal,
+                                                                            };
+                                                                            cursor.set(Some((pos, direction)));
+                                                                        }
+                                                                        None => {}
+                                                                    }
+                                                                },
+                                                                ondragover: move |event| event.prevent_default(),
+                                                                ondrop: move |event| {
+                                                                    event.prevent_default();
+                                                                    let drag = *dragging.read();
+                                                                    dragging.set(None);
+                                                                    if !your_turn || exchanging.read().is_some() {
+                                                                        return;
+                                                                    }
+                                                                    match drag {
+                                                                        Some(Drag::Rack(rack_index)) => place_tile(pos, rack_index),
+                                                                        Some(Drag::Board(from)) => {
+                                                                            let free = game.read().board.get_cell_pos(pos).is_empty()
+                                                                                && placements.read().iter().all(|p| p.pos != pos);
+                                                                            if free {
+                                                                                for placement in placements.write().iter_mut() {
+                                                                                    if placement.pos == from {
+                                                                                        placement.pos = pos;
+                                                                                    }
+                                                                                }
+                                                                            }
+                                                                        }
+                                                                        None => {}
                                                                     }
                                                                 },
-                                                                style: "{square_style}",
-                                                                if cell != ' ' {
-                                                                    div { style: "background:#f4e4bc;border:1px solid #8b4513;border-radius:3px;width:100%;height:100%;display:flex;align-items:center;justify-content:center;font-weight:bold;color:#2f4f4f;",
-                                                                        "{cell}"
+                                                                style: "{square_style}{outline}",
+                                                                if let Some(tile) = tile {
+                                                                    div {
+                                                                        style: "{tile_style}",
+                                                                        draggable: placement.is_some(),
+                                                                        ondragstart: move |_| dragging.set(Some(Drag::Board(pos))),
+                                                                        ondragend: move |_| dragging.set(None),
+                                                                        "{tile}"
                                                                     }
                                                                 } else {
                                                                     div { style: "font-size:10px;line-height:1;", "{square_label}" }
@@ -343,52 +660,222 @@ pub fn scrabble_board() -> Element {
             div {
                 class: "tile-rack",
                 style: "margin:20px auto;text-align:center;max-width:600px;",
-                h3 { "Letter Tiles" }
+                // a tile dragged off the board goes back, one dragged along the rack goes to the end
+                ondragover: move |event| event.prevent_default(),
+                ondrop: move |event| {
+                    event.prevent_default();
+                    let drag = *dragging.read();
+                    dragging.set(None);
+                    match drag {
+                        Some(Drag::Board(from)) => placements.write().retain(|p| p.pos != from),
+                        Some(Drag::Rack(from)) => move_on_rack(from, usize::MAX),
+                        None => {}
+                    }
+                },
+                h3 { "{game_state.player_name[rack_player]}'s rack" }
                 div { style: "display:flex;flex-wrap:wrap;justify-content:center;gap:5px;",
                     {
-                        TILES
-                            .iter()
-                            .map(|&tile| {
-                                let is_selected = *selected_tile.read() == Some(tile);
+                        rack.iter()
+                            .enumerate()
+                            .filter(|(i, _)| placed.iter().all(|p| p.rack_index != *i))
+                            .filter(|(i, _)| pending_blank.is_none_or(|(_, rack_index)| rack_index != *i))
+                            .map(|(i, &tile)| {
+                                let is_selected = match &chosen {
+                                    Some(chosen) => chosen.contains(&i),
+                                    None => *selected_tile.read() == Some(i),
+                                };
+                                let value = tile.score(variant);
                                 rsx! {
                                     button {
-                                        onclick: move |_| selected_tile.set(Some(tile)),
-                                        style: "margin:2px;padding:8px 12px;font-size:16px;font-weight:bold;background:#f4e4bc;border:2px solid #8b4513;border-radius:5px;cursor:pointer;color:#2f4f4f;min-width:40px;",
+                                        onclick: move |_| {
+                                            let chosen = exchanging.read().clone();
+                                            match chosen {
+                                                Some(mut chosen) => {
+                                                    match chosen.iter().position(|&j| j == i) {
+                                                        Some(at) => {
+                                                            chosen.remove(at);
+                                                        }
+                                                        None => chosen.push(i),
+                                                    }
+                                                    exchanging.set(Some(chosen));
+                                                }
+                                                None => selected_tile.set(Some(i)),
+                                            }
+                                        },
+                                        draggable: true,
+                                        ondragstart: move |_| dragging.set(Some(Drag::Rack(i))),
+                                        ondragend: move |_| dragging.set(None),
+                                        ondragover: move |event| event.prevent_default(),
+                                        // dropped on another tile on the rack it takes that one's place
+                                        ondrop: move |event| {
+                                            event.prevent_default();
+                                            event.stop_propagation();
+                                            let drag = *dragging.read();
+                                            dragging.set(None);
+                                            match drag {
+                                                Some(Drag::Rack(from)) if from != i => move_on_rack(from, i),
+                                                Some(Drag::Board(from)) => placements.write().retain(|p| p.pos != from),
+                                                _ => {}
+                                            }
+                                        },
+                                        style: get_rack_tile_style(is_selected, chosen.is_some()),
                                         class: if is_selected { "selected-tile" } else { "" },
                                         "{tile}"
+                                        sub { style: "font-size:9px;", "{value}" }
                                     }
                                 }
                             })
                     }
                 }
+                div { style: "margin:10px;",
+                    button {
+                        onclick: shuffle,
+                        style: "padding:6px 14px;font-size:14px;background:#f0f0f0;color:#333;border:1px solid #ccc;border-radius:5px;cursor:pointer;margin:5px;",
+                        "Shuffle"
+                    }
+                }
             }
 
-            div { style: "margin:20px auto;text-align:center;font-size:18px;",
-                "Selected tile: "
-                {
-                    let tile = selected_tile.read();
-                    match *tile {
-                        Some(t) => format!("{}", t),
-                        None => "None".to_string(),
+            if let Some((pos, rack_index)) = pending_blank {
+                div {
+                    class: "blank-letters",
+                    style: "margin:10px auto;text-align:center;max-width:600px;",
+                    h3 { "Which letter is the blank?" }
+                    div { style: "display:flex;flex-wrap:wrap;justify-content:center;gap:4px;",
+                        {
+                            ALPHABET
+                                .iter()
+                                .map(|&letter| {
+                                    rsx! {
+                                        button {
+                                            onclick: move |_| {
+                                                placements
+                                                    .write()
+                                                    .push(Placement {
+                                                        pos,
+                                                        tile: Tile::Blank {
+                                                            acting_as_letter: Some(letter),
+                                                        },
+                                                        rack_index,
+                                                    });
+                                                blank_square.set(None);
+                                            },
+                                            style: "padding:6px 10px;font-size:14px;font-weight:bold;background:#f4e4bc;border:1px solid #8b4513;border-radius:4px;cursor:pointer;color:#2f4f4f;",
+                                            "{letter.as_char().to_ascii_lowercase()}"
+                                        }
+                                    }
+                                })
+                        }
+                        button {
+                            onclick: move |_| blank_square.set(None),
+                            style: "padding:6px 10px;font-size:14px;background:#f0f0f0;color:#333;border:1px solid #ccc;border-radius:4px;cursor:pointer;",
+                            "Cancel"
+                        }
                     }
                 }
             }
+            match preview {
+                Some(Ok(text)) => rsx! {
+                    div { style: "margin:10px auto;text-align:center;font-size:16px;color:#2e7d32;", "{text}" }
+                },
+                Some(Err(text)) => rsx! {
+                    div { style: "margin:10px auto;text-align:center;font-size:16px;color:#c62828;", "{text}" }
+                },
+                None => rsx! {},
+            }
+            if chosen.is_some() {
+                div { style: "margin:10px auto;text-align:center;font-size:16px;",
+                    "Pick the tiles to put back in the bag"
+                }
+            }
             div { style: "margin:20px auto;text-align:center;",
-                button {
-                    onclick: move |_| {
-                        board.set(vec![vec![' '; BOARD_SIZE]; BOARD_SIZE]);
-                        selected_tile.set(None);
-                    },
-                    style: "padding:10px 20px;font-size:16px;background:#ff6b6b;color:white;border:none;border-radius:5px;cursor:pointer;margin:10px;",
-                    "Clear Board"
+                if let Some(chosen) = &chosen {
+                    button {
+                        onclick: exchange,
+                        disabled: !your_turn || chosen.is_empty(),
+                        style: "padding:10px 20px;font-size:16px;background:#4CAF50;color:white;border:none;border-radius:5px;cursor:pointer;margin:10px;",
+                        "Exchange {chosen.len()} tiles"
+                    }
+                    button {
+                        onclick: move |_| exchanging.set(None),
+                        style: "padding:10px 20px;font-size:16px;background:#f0f0f0;color:#333;border:1px solid #ccc;border-radius:5px;cursor:pointer;margin:10px;",
+                        "Cancel"
+                    }
+                } else {
+                    button {
+                        onclick: move |_| play(),
+                        disabled: !your_turn,
+                        style: "padding:10px 20px;font-size:16px;background:#4CAF50;color:white;border:none;border-radius:5px;cursor:pointer;margin:10px;",
+                        "Play"
+                    }
+                    button {
+                        onclick: move |_| recall(),
+                        style: "padding:10px 20px;font-size:16px;background:#f0f0f0;color:#333;border:1px solid #ccc;border-radius:5px;cursor:pointer;margin:10px;",
+                        "Recall"
+                    }
+                    button {
+                        onclick: exchange,
+                        disabled: !your_turn,
+                        style: "padding:10px 20px;font-size:16px;background:#87ceeb;color:#000080;border:none;border-radius:5px;cursor:pointer;margin:10px;",
+                        "Exchange"
+                    }
+                    button {
+                        onclick: pass,
+                        disabled: !your_turn,
+                        style: "padding:10px 20px;font-size:16px;background:#ff6b6b;color:white;border:none;border-radius:5px;cursor:pointer;margin:10px;",
+                        "Pass"
+                    }
                 }
             }
-            div { style: "margin:20px auto;text-align:center;font-style:italic;color:#666;",
-                "Current layout: "
-                {
-                    match *current_layout.read() {
-                        BoardLayout::Traditional => "Traditional Scrabble (US/UK/International)",
-                        BoardLayout::Wordfeud => "Wordfeud (More double letter squares)",
+            if let Some(text) = message.read().clone() {
+                div { style: "margin:20px auto;text-align:center;font-size:18px;", "{text}" }
+            }
+            if let Some(summary) = summary {
+                div {
+                    class: "summary",
+                    style: "margin:20px auto;text-align:center;",
+                    h3 {
+                        {
+                            match game_state.winner {
+                                Some(winner) => format!("Game over, {} won", game_state.player_name[winner]),
+                                None => "Game over, it's a draw".to_string(),
+                            }
+                        }
+                    }
+                    table { style: "margin:0 auto;border-collapse:collapse;",
+                        thead {
+                            tr {
+                                th { style: "padding:4px 10px;", "Player" }
+                                th { style: "padding:4px 10px;", "Score" }
+                                th { style: "padding:4px 10px;", "Moves" }
+                                th { style: "padding:4px 10px;", "Bingos" }
+                                th { style: "padding:4px 10px;", "Per turn" }
+                                th { style: "padding:4px 10px;", "Best play" }
+                            }
+                        }
+                        tbody {
+                            {
+                                summary
+                                    .players
+                                    .iter()
+                                    .map(|player| {
+                                        let best = match &player.highest {
+                                            Some(high) => format!("{} {}", high.word, high.score),
+                                            None => "-".to_string(),
+                                        };
+                                        rsx! {
+                                            tr {
+                                                td { style: "padding:4px 10px;", "{player.name}" }
+                                                td { style: "padding:4px 10px;", "{player.score}" }
+                                                td { style: "padding:4px 10px;", "{player.moves}" }
+                                                td { style: "padding:4px 10px;", "{player.bingos}" }
+                                                td { style: "padding:4px 10px;", "{player.average_per_turn:.1}" }
+                                                td { style: "padding:4px 10px;", "{best}" }
+                                            }
+                                        }
+                                    })
+                            }
+                        }
                     }
                 }
             }
@@ -20,6 +20,11 @@ fn main() {
 
     #[cfg(feature = "web")]
     {
+        // the computer's web worker loads this program too, but only to think
+        #[cfg(target_arch = "wasm32")]
+        if web_sys::window().is_none() {
+            return;
+        }
         dioxus::launch(App);
     }
 }
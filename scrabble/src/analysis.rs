@@ -0,0 +1,280 @@
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use web_time::Instant;
+
+use crate::bot::{best_exchange, equity, leave_after, leave_value};
+use crate::tiles::TileBag;
+use crate::{Game, GameMove, GameMoveRecordDetail, MoveError, TScore};
+
+// a move the analysis found, e.g. "H8 Horizontal QUIZ 45"
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FoundMove {
+    pub position: String,
+    pub direction: String,
+    pub word: String,
+    pub score: TScore,
+    pub equity: f32,
+}
+
+impl FoundMove {
+    pub fn new(game: &Game, rack: &TileBag, game_move: &GameMove) -> Self {
+        Self {
+            position: game_move.starting_position.to_string(),
+            direction: game_move.direction.to_string(),
+            word: game_move.word(&game.board),
+            score: game_move.score,
+            equity: equity(rack, game_move),
+        }
+    }
+}
+
+impl Display for FoundMove {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} {} {}",
+            self.position, self.direction, self.word, self.score
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TurnAnalysis {
+    pub turn: usize,
+    pub player: usize,
+    pub rack: String,
+    pub played: String,
+    pub score: TScore,
+    pub equity: f32,
+    pub best_by_score: Option<FoundMove>,
+    pub best_by_equity: Option<FoundMove>,
+    pub points_lost: TScore,
+    pub equity_lost: f32,
+    pub missed_bingo: Option<FoundMove>,
+    // the word which isn't in the word list
+    pub phony: Option<String>,
+}
+
+impl TurnAnalysis {
+    // a turn within half a point of the best equity counts as finding the best move
+    pub fn is_best(&self) -> bool {
+        self.equity_lost < 0.5
+    }
+}
+
+impl Display for TurnAnalysis {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:3} {:7} {:24} {:4}",
+            self.turn + 1,
+            self.rack,
+            self.played,
+            self.score
+        )?;
+        if let Some(phony) = &self.phony {
+            write!(f, "  phony {}", phony)?;
+        }
+        if !self.is_best() {
+            if let Some(best) = &self.best_by_equity {
+                write!(f, "  best {} ({:.1} equity lost)", best, self.equity_lost)?;
+            }
+        }
+        if let Some(bingo) = &self.missed_bingo {
+            write!(f, "  missed bingo {}", bingo)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PlayerAnalysis {
+    pub name: String,
+    pub turns: usize,
+    pub best_moves: usize,
+    pub points_lost: TScore,
+    pub equity_lost: f32,
+    pub missed_bingos: usize,
+    pub phonies: usize,
+    // the percentage of turns where the best move was found
+    pub accuracy: f32,
+}
+
+impl Display for PlayerAnalysis {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:16} {:5.1}% accuracy, best move {} of {} turns, {} points and {:.1} equity lost, {} missed bingos, {} phonies",
+            self.name,
+            self.accuracy,
+            self.best_moves,
+            self.turns,
+            self.points_lost,
+            self.equity_lost,
+            self.missed_bingos,
+            self.phonies
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GameAnalysis {
+    pub turns: Vec<TurnAnalysis>,
+    pub players: Vec<PlayerAnalysis>,
+}
+
+impl GameAnalysis {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+}
+
+impl Display for GameAnalysis {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for turn in self.turns.iter() {
+            writeln!(f, "{}", turn)?;
+        }
+        writeln!(f)?;
+        for player in self.players.iter() {
+            writeln!(f, "{}", player)?;
+        }
+        Ok(())
+    }
+}
+
+/*
+Replays the game from the racks recorded with each move, and finds what could have been played.
+With a time per turn the search for each turn is cut short, see Game::legal_moves.
+*/
+pub fn analyse_game(game: &Game, time_per_turn: Option<Duration>) -> GameAnalysis {
+    let mut replay = game.replay();
+    let mut turns = vec![];
+    // the tiles not yet on the board, to work out whether there were enough in the bag to change
+    let mut tiles_left = TileBag::new(game.scrabble_variant).count() as usize;
+
+    for (turn, record) in game.moves.iter().enumerate() {
+        replay.replay_turn(record);
+        let rack = record.rack;
+
+        let moves = replay.legal_moves(time_per_turn.map(|t| Instant::now() + t));
+        let best_by_score = moves.iter().max_by_key(|m| m.score);
+        let best_by_equity = moves
+            .iter()
+            .max_by(|a, b| equity(&rack, a).total_cmp(&equity(&rack, b)));
+        let best_bingo = moves
+            .iter()
+            .filter(|m| m.tiles.len() == 7)
+            .max_by_key(|m| m.score);
+
+        let (played, score, played_equity, tiles_played, phony) = match &record.detail {
+            GameMoveRecordDetail::Move {
+                starting_position,
+                direction,
+                tiles,
+                score,
+                word,
+            } => {
+                let phony = match replay.validate_move(*starting_position, *direction, tiles) {
+                    Err(MoveError::InvalidWord(word)) => Some(word),
+                    _ => None,
+                };
+                (
+                    format!("{} {}", starting_position, word),
+                    *score,
+                    *score as f32 + leave_value(&leave_after(&rack, tiles)),
+                    tiles.len(),
+                    phony,
+                )
+            }
+            GameMoveRecordDetail::Exchange { tiles } => (
+                format!("Exchange {}", tiles),
+                0,
+                leave_value(&leave_after(&rack, tiles)),
+                0,
+                None,
+            ),
+            GameMoveRecordDetail::Pass => ("Pass".to_string(), 0, leave_value(&rack), 0, None),
+        };
+
+        // changing tiles might have been the best choice, so there is nothing lost by it
+        let bag_count = tiles_left.saturating_sub(7 * game.number_of_players);
+        let exchange_equity = match bag_count >= 7 {
+            true => best_exchange(&rack).map_or(f32::MIN, |e| e.equity),
+            false => f32::MIN,
+        };
+        let best_equity = best_by_equity
+            .map(|m| equity(&rack, m))
+            .unwrap_or(played_equity)
+            .max(exchange_equity);
+        tiles_left -= tiles_played;
+        turns.push(TurnAnalysis {
+            turn,
+            player: record.player,
+            rack: rack.to_string(),
+            played,
+            score,
+            equity: played_equity,
+            best_by_score: best_by_score.map(|m| FoundMove::new(&replay, &rack, m)),
+            best_by_equity: best_by_equity.map(|m| FoundMove::new(&replay, &rack, m)),
+            points_lost: best_by_score.map_or(0, |m| (m.score - score).max(0)),
+            equity_lost: (best_equity - played_equity).max(0.0),
+            missed_bingo: match tiles_played {
+                7 => None,
+                _ => best_bingo.map(|m| FoundMove::new(&replay, &rack, m)),
+            },
+            phony,
+        });
+
+        replay.replay_record(record);
+    }
+
+    let players = (0..game.number_of_players)
+        .map(|player| {
+            let mine: Vec<&TurnAnalysis> = turns.iter().filter(|t| t.player == player).collect();
+            let best_moves = mine.iter().filter(|t| t.is_best()).count();
+            PlayerAnalysis {
+                name: game.player_name[player].clone(),
+                turns: mine.len(),
+                best_moves,
+                points_lost: mine.iter().map(|t| t.points_lost).sum(),
+                equity_lost: mine.iter().map(|t| t.equity_lost).sum(),
+                missed_bingos: mine.iter().filter(|t| t.missed_bingo.is_some()).count(),
+                phonies: mine.iter().filter(|t| t.phony.is_some()).count(),
+                accuracy: 100.0 * best_moves as f32 / mine.len().max(1) as f32,
+            }
+        })
+        .collect();
+
+    GameAnalysis { turns, players }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pos::Position;
+    use crate::tests::two_player_game;
+    use crate::tiles::TileList;
+    use crate::word_list::WordCheck;
+    use crate::Direction;
+
+    #[test]
+    fn test_analyse_game() {
+        let mut game = two_player_game(WordCheck::Exact, "AEINRST");
+        let tiles = TileList::try_from("AT").unwrap();
+        game.human_move(Position::new(7, 7), Direction::Horizontal, &tiles)
+            .unwrap();
+
+        let analysis = analyse_game(&game, None);
+        assert_eq!(analysis.turns.len(), 1);
+        let turn = &analysis.turns[0];
+        assert_eq!(turn.score, 4);
+        assert!(turn.points_lost > 50);
+        assert!(turn.missed_bingo.is_some());
+        assert!(turn.phony.is_none());
+        assert_eq!(analysis.players[0].turns, 1);
+        assert_eq!(analysis.players[0].accuracy, 0.0);
+        assert!(analysis.to_json().contains("missed_bingo"));
+    }
+}
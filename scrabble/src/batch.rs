@@ -0,0 +1,311 @@
+use std::fmt::{Display, Formatter};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::board::ScrabbleVariant;
+use crate::strategy::Strategy;
+use crate::word_list::WordCheck;
+use crate::{Game, GameMoveRecordDetail, Player, TScore};
+
+#[derive(Debug, Clone)]
+pub struct Contestant {
+    pub name: String,
+    pub strategy: Arc<dyn Strategy>,
+}
+
+impl Contestant {
+    pub fn new(strategy: Arc<dyn Strategy>) -> Self {
+        Self {
+            name: strategy.name(),
+            strategy,
+        }
+    }
+}
+
+/*
+How a match between two strategies is played.  The games are played in pairs drawing the
+same tiles, seed + game / 2, with each contestant going first in one of the pair.
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct BatchConfig {
+    pub games: usize,
+    pub seed: u64,
+    pub threads: usize,
+    pub scrabble_variant: &'static ScrabbleVariant,
+    pub word_check: WordCheck,
+}
+
+impl BatchConfig {
+    // uses every core
+    pub fn new(games: usize, seed: u64, scrabble_variant: &'static ScrabbleVariant) -> Self {
+        Self {
+            games,
+            seed,
+            threads: thread::available_parallelism().map_or(1, |n| n.get()),
+            scrabble_variant,
+            word_check: WordCheck::Exact,
+        }
+    }
+}
+
+// one game, everything indexed by contestant rather than by who went first
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GameResult {
+    pub game: usize,
+    pub seed: u64,
+    pub first: usize,
+    pub scores: [TScore; 2],
+    pub bingos: [usize; 2],
+    pub turns: [usize; 2],
+    pub seconds: [f64; 2],
+}
+
+impl GameResult {
+    // positive when the first contestant won
+    pub fn spread(&self) -> TScore {
+        self.scores[0] - self.scores[1]
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ContestantSummary {
+    pub name: String,
+    pub wins: usize,
+    pub average_score: f64,
+    pub bingos_per_game: f64,
+    pub seconds_per_move: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BatchSummary {
+    pub games: usize,
+    pub draws: usize,
+    // from the first contestant's point of view
+    pub mean_spread: f64,
+    // the half width of the 95% confidence interval of the mean spread
+    pub spread_margin: f64,
+    pub contestants: [ContestantSummary; 2],
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BatchReport {
+    pub summary: BatchSummary,
+    pub results: Vec<GameResult>,
+}
+
+fn play_one(contestants: &[Contestant; 2], config: &BatchConfig, game_number: usize) -> GameResult {
+    let first = game_number % 2;
+    let order = [first, 1 - first];
+    let seed = config.seed + (game_number / 2) as u64;
+    let mut game = Game::new_with_word_check(
+        config.scrabble_variant,
+        2,
+        std::array::from_fn(|i| Player::with_strategy(contestants[order[i % 2]].strategy.clone())),
+        order.iter().map(|&c| contestants[c].name.clone()).collect(),
+        config.word_check,
+    );
+    game.restart_with_seed(seed);
+    while !game.is_over {
+        game.computer_move();
+    }
+
+    let mut result = GameResult {
+        game: game_number,
+        seed,
+        first,
+        scores: [0; 2],
+        bingos: [0; 2],
+        turns: [0; 2],
+        seconds: [0.0; 2],
+    };
+    for (i, &c) in order.iter().enumerate() {
+        result.scores[c] = game.player[i].score;
+        result.seconds[c] = game.player[i].timer.elapsed().as_secs_f64();
+    }
+    for record in game.moves.iter() {
+        let c = order[record.player];
+        result.turns[c] += 1;
+        if let GameMoveRecordDetail::Move { tiles, .. } = &record.detail {
+            if tiles.len() == 7 {
+                result.bingos[c] += 1;
+            }
+        }
+    }
+    result
+}
+
+pub fn summarise(names: [&str; 2], results: &[GameResult]) -> BatchSummary {
+    let games = results.len();
+    let n = games.max(1) as f64;
+    let spreads: Vec<f64> = results.iter().map(|r| r.spread() as f64).collect();
+    let mean_spread = spreads.iter().sum::<f64>() / n;
+    let variance = match games {
+        0 | 1 => 0.0,
+        _ => {
+            spreads
+                .iter()
+                .map(|s| (s - mean_spread).powi(2))
+                .sum::<f64>()
+                / (n - 1.0)
+        }
+    };
+    let contestant = |c: usize| {
+        let turns: usize = results.iter().map(|r| r.turns[c]).sum();
+        ContestantSummary {
+            name: names[c].to_string(),
+            wins: results
+                .iter()
+                .filter(|r| r.scores[c] > r.scores[1 - c])
+                .count(),
+            average_score: results.iter().map(|r| r.scores[c] as f64).sum::<f64>() / n,
+            bingos_per_game: results.iter().map(|r| r.bingos[c]).sum::<usize>() as f64 / n,
+            seconds_per_move: results.iter().map(|r| r.seconds[c]).sum::<f64>()
+                / turns.max(1) as f64,
+        }
+    };
+    BatchSummary {
+        games,
+        draws: results.iter().filter(|r| r.spread() == 0).count(),
+        mean_spread,
+        spread_margin: 1.96 * (variance / n).sqrt(),
+        contestants: [contestant(0), contestant(1)],
+    }
+}
+
+// plays the games spread over the threads, the results come back in game order
+pub fn run_batch(contestants: &[Contestant; 2], config: &BatchConfig) -> BatchReport {
+    let next_game = AtomicUsize::new(0);
+    let results = Mutex::new(Vec::with_capacity(config.games));
+    thread::scope(|scope| {
+        for _ in 0..config.threads.max(1) {
+            scope.spawn(|| loop {
+                let game_number = next_game.fetch_add(1, Ordering::Relaxed);
+                if game_number >= config.games {
+                    break;
+                }
+                let result = play_one(contestants, config, game_number);
+                results.lock().unwrap().push(result);
+            });
+        }
+    });
+    let mut results = results.into_inner().unwrap();
+    results.sort_by_key(|r| r.game);
+    BatchReport {
+        summary: summarise(
+            [contestants[0].name.as_str(), contestants[1].name.as_str()],
+            &results,
+        ),
+        results,
+    }
+}
+
+impl BatchReport {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+
+    // one line per game
+    pub fn to_csv(&self) -> String {
+        let names = &self.summary.contestants;
+        let mut csv = format!(
+            "game,seed,first,{0} score,{1} score,spread,{0} bingos,{1} bingos,{0} turns,{1} turns,{0} seconds,{1} seconds\n",
+            names[0].name, names[1].name
+        );
+        for r in self.results.iter() {
+            csv.push_str(&format!(
+                "{},{},{},{},{},{},{},{},{},{},{:.3},{:.3}\n",
+                r.game,
+                r.seed,
+                names[r.first].name,
+                r.scores[0],
+                r.scores[1],
+                r.spread(),
+                r.bingos[0],
+                r.bingos[1],
+                r.turns[0],
+                r.turns[1],
+                r.seconds[0],
+                r.seconds[1]
+            ));
+        }
+        csv
+    }
+}
+
+impl Display for BatchSummary {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{} games, {} draws", self.games, self.draws)?;
+        writeln!(
+            f,
+            "Mean spread {:+.1} ± {:.1} for {}",
+            self.mean_spread, self.spread_margin, self.contestants[0].name
+        )?;
+        for c in self.contestants.iter() {
+            writeln!(
+                f,
+                "{:24} {:4} wins {:6.1} average {:4.2} bingos per game {:>10.3?} per move",
+                c.name,
+                c.wins,
+                c.average_score,
+                c.bingos_per_game,
+                Duration::from_secs_f64(c.seconds_per_move)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::SCRABBLE_VARIANT_OFFICIAL;
+    use crate::strategy::{Action, GameView};
+
+    // quick to play, and the scores only depend on the tiles drawn
+    #[derive(Debug)]
+    struct AlwaysExchange;
+
+    impl Strategy for AlwaysExchange {
+        fn name(&self) -> String {
+            "Always exchange".to_string()
+        }
+
+        fn choose_action(&self, view: &GameView) -> Action {
+            Action::Exchange(view.rack().into())
+        }
+    }
+
+    #[test]
+    fn test_run_batch() {
+        let contestants = [
+            Contestant::new(Arc::new(AlwaysExchange)),
+            Contestant {
+                name: "Other".to_string(),
+                strategy: Arc::new(AlwaysExchange),
+            },
+        ];
+        let config = BatchConfig {
+            threads: 2,
+            ..BatchConfig::new(4, 7, &SCRABBLE_VARIANT_OFFICIAL)
+        };
+        let report = run_batch(&contestants, &config);
+        assert_eq!(report.results.len(), 4);
+        assert_eq!(report.results[0].seed, report.results[1].seed);
+        assert_ne!(report.results[0].first, report.results[1].first);
+        assert!(report.results.iter().all(|r| r.turns == [3, 3]));
+        assert_eq!(report.summary.games, 4);
+        assert_eq!(report.to_csv().lines().count(), 5);
+        assert!(report.to_json().contains("mean_spread"));
+
+        // the same seed draws the same tiles
+        let again = run_batch(&contestants, &config);
+        assert_eq!(
+            again.results.iter().map(|r| r.scores).collect::<Vec<_>>(),
+            report.results.iter().map(|r| r.scores).collect::<Vec<_>>()
+        );
+    }
+}
@@ -5,13 +5,12 @@ use std::fmt::Formatter;
 
 use crate::pos::Position;
 use crate::tiles::Letter;
-use crate::tiles::ALPHABET;
 use crate::Direction;
 use crate::MoveError;
 
 use crate::tiles::LetterSet;
 use crate::tiles::Tile;
-use crate::word_list::is_word;
+use crate::word_list::WordCheck;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub(crate) enum CellValue {
@@ -94,7 +93,7 @@ impl CellValue {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub(crate) enum CellType {
+pub enum CellType {
     Blank,
     DoubleLetter,
     TripleLetter,
@@ -162,6 +161,7 @@ impl Display for Cell {
                 self.cell_type.as_char(),
                 AnsiCodes::BOLDOFF
             ),
+            // a blank shows the letter it stands for in lower case
             CellValue::Filled {
                 letter,
                 is_blank,
@@ -169,16 +169,18 @@ impl Display for Cell {
             } => {
                 write!(
                     f,
-                    "{}{}{}{}{} {}",
+                    "{}{} {} {}",
                     if populated_last_move {
                         AnsiCodes::BOLDON
                     } else {
                         ""
                     },
                     if is_blank { BLANKCOLOUR } else { LETTERCOLOUR },
-                    if is_blank { "*" } else { " " },
-                    LETTERCOLOUR,
-                    letter,
+                    if is_blank {
+                        letter.as_char().to_ascii_lowercase()
+                    } else {
+                        letter.as_char()
+                    },
                     AnsiCodes::BOLDOFF
                 )
             }
@@ -201,7 +203,7 @@ impl Debug for Cell {
                 letter, is_blank, ..
             } => {
                 if is_blank {
-                    write!(f, "{}{}", "*", letter)
+                    write!(f, "{}", letter.as_char().to_ascii_lowercase())
                 } else {
                     write!(
                         f,
@@ -218,10 +220,39 @@ impl Debug for Cell {
 }
 
 impl Cell {
-    pub(crate) fn is_empty(&self) -> bool {
+    pub fn is_empty(&self) -> bool {
         self.value.is_empty()
     }
 
+    pub fn cell_type(&self) -> CellType {
+        self.cell_type
+    }
+
+    // the tile on the square, a blank with the letter it stands for
+    pub fn tile(&self) -> Option<Tile> {
+        match self.value {
+            CellValue::Filled {
+                letter,
+                is_blank: true,
+                ..
+            } => Some(Tile::Blank {
+                acting_as_letter: Some(letter),
+            }),
+            CellValue::Filled { letter, .. } => Some(Tile::Letter(letter)),
+            CellValue::Empty { .. } => None,
+        }
+    }
+
+    pub fn is_last_move(&self) -> bool {
+        matches!(
+            self.value,
+            CellValue::Filled {
+                populated_last_move: true,
+                ..
+            }
+        )
+    }
+
     pub(crate) fn as_filled(&self) -> Option<Letter> {
         self.value.as_filled()
     }
@@ -327,6 +358,7 @@ pub const SCRABBLE_VARIANT_WORDFEUD: ScrabbleVariant = ScrabbleVariant {
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct Board {
     pub(crate) scrabble_variant: &'static ScrabbleVariant,
+    pub(crate) word_check: WordCheck,
     pub(crate) cells: [Cell; 225],
 }
 
@@ -394,9 +426,10 @@ impl Board {
     pub fn is_valid_position(&self, pos: Position) -> bool {
         pos.x < self.scrabble_variant.width && pos.y < self.scrabble_variant.height
     }
-    pub(crate) fn new(variant: &'static ScrabbleVariant) -> Board {
+    pub(crate) fn new(variant: &'static ScrabbleVariant, word_check: WordCheck) -> Board {
         let mut board = Board {
             scrabble_variant: variant,
+            word_check,
             cells: [Cell {
                 value: CellValue::Empty {
                     horizontal_letters: LetterSet::new_full(),
@@ -457,6 +490,24 @@ impl Board {
         word
     }
 
+    // the word as it is written down, with any blanks in it in lower case
+    pub fn read_played_word_at_pos(
+        &self,
+        reference_position: Position,
+        direction: Direction,
+    ) -> String {
+        let mut pos_iterator = self.start_of_word(reference_position, direction);
+        let mut word = String::new();
+        while let Some(tile) = self.get_cell_pos(pos_iterator).tile() {
+            word.push_str(&tile.to_string());
+            match pos_iterator.try_step_forward(direction) {
+                Some(next_position) => pos_iterator = next_position,
+                None => break,
+            }
+        }
+        word
+    }
+
     /*
     For a given cell identify the word it is contained in, the central word.
     Also, if they exist, the previous word and the next word. A gap of only one cell is allowed.
@@ -480,12 +531,7 @@ impl Board {
                     _ => {}
                 }
             }
-            let mut ls = LetterSet::new_empty();
-            for l in ALPHABET {
-                if is_word(format!("{}{}{}", previous_word, l, central_word).as_str()) {
-                    ls.add(*l);
-                }
-            }
+            let ls = self.word_check.hook_letters(&previous_word, &central_word);
             self.get_cell_pos_mut(previous_gap)
                 .value
                 .set_letter_set(-direction, ls);
@@ -503,12 +549,7 @@ impl Board {
                     _ => {}
                 }
             }
-            let mut ls = LetterSet::new_empty();
-            for l in ALPHABET {
-                if is_word(format!("{}{}{}", central_word, l, next_word).as_str()) {
-                    ls.add(*l);
-                }
-            }
+            let ls = self.word_check.hook_letters(&central_word, &next_word);
             self.get_cell_pos_mut(next_gap)
                 .value
                 .set_letter_set(-direction, ls);
@@ -574,7 +615,7 @@ impl Board {
             cross_pos = cross_pos_next;
         }
 
-        if !is_word(&cross_word) {
+        if !self.word_check.is_valid(&cross_word) {
             return Err(MoveError::InvalidWord(cross_word));
         }
         Ok(cross_score * central_cell.cell_type.word_multiplier() as i16)
@@ -630,6 +671,14 @@ impl Board {
             }
         }
     }
+    // the squares filled by the last move, so it can be highlighted
+    pub fn last_move_positions(&self) -> Vec<Position> {
+        let variant = self.scrabble_variant;
+        (0..variant.height)
+            .flat_map(|y| (0..variant.width).map(move |x| Position::new(x, y)))
+            .filter(|&pos| self.get_cell_pos(pos).is_last_move())
+            .collect()
+    }
 }
 
 pub enum MoveCell {
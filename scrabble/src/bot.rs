@@ -0,0 +1,497 @@
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use web_time::Instant;
+
+use crate::board::{Board, CellType};
+use crate::pos::Position;
+use crate::strategy::{Action, GameView, Strategy};
+use crate::tiles::{TileBag, TileList};
+use crate::word_list::{frequency_rank, has_frequency_list};
+use crate::{Direction, GameMove, TScore};
+
+// the rough value of keeping each letter on the rack, A to Z
+const LETTER_LEAVE_VALUES: [f32; 26] = [
+    1.0, -2.0, 0.5, 0.5, 1.5, -2.0, -2.0, 0.5, -0.5, -3.0, -2.5, -0.5, 0.0, 0.5, -1.0, -0.5, -7.0,
+    1.0, 7.5, 0.5, -3.0, -5.5, -3.0, 3.0, -0.5, 2.0,
+];
+const BLANK_LEAVE_VALUE: f32 = 25.0;
+const DUPLICATE_PENALTY: f32 = 3.0;
+const VOWELS: [usize; 5] = [0, 4, 8, 14, 20];
+// the letters of the most common bingos, which a bingo hunter holds on to
+const BINGO_LETTERS: [usize; 7] = [0, 4, 8, 13, 17, 18, 19];
+// how many of the best moves are looked at more closely when there is time, and how often
+const SIMULATION_CANDIDATES: usize = 5;
+const SIMULATION_ROUNDS: usize = 50;
+
+/*
+How much the tiles left on the rack are worth for the next turn.  Good letters and blanks
+add to it, duplicates and too many vowels or consonants take away.
+*/
+pub fn leave_value(leave: &TileBag) -> f32 {
+    let mut value = leave.blanks as f32 * BLANK_LEAVE_VALUE;
+    let mut vowels = 0i32;
+    let mut consonants = 0i32;
+    for (letter, &count) in leave.letters.iter().enumerate() {
+        if count == 0 {
+            continue;
+        }
+        value += LETTER_LEAVE_VALUES[letter] * count as f32;
+        value -= DUPLICATE_PENALTY * (count - 1) as f32;
+        if VOWELS.contains(&letter) {
+            vowels += count as i32;
+        } else {
+            consonants += count as i32;
+        }
+    }
+    // best to keep about one vowel for every consonant
+    value - 1.5 * ((vowels - consonants).abs() - 1).max(0) as f32
+}
+
+fn bingo_letters(leave: &TileBag) -> f32 {
+    let letters: u8 = BINGO_LETTERS
+        .iter()
+        .map(|&letter| leave.letters[letter].min(1))
+        .sum();
+    (letters + leave.blanks) as f32
+}
+
+/*
+How many premium word squares the move opens up for the opponent, counting empty double
+and triple word squares within two cells of the tiles played.
+*/
+pub fn openness(board: &Board, tile_positions: &[Position]) -> f32 {
+    let mut seen = HashSet::new();
+    let mut openness = 0.0;
+    for position in tile_positions {
+        for direction in [Direction::Horizontal, Direction::Vertical] {
+            let mut forward = Some(*position);
+            let mut backward = Some(*position);
+            for _ in 0..2 {
+                forward = forward.and_then(|p| p.try_step_forward(direction));
+                backward = backward.and_then(|p| p.try_step_backward(direction));
+                for p in [forward, backward].into_iter().flatten() {
+                    let cell = board.get_cell_pos(p);
+                    if cell.is_empty() && !tile_positions.contains(&p) && seen.insert(p) {
+                        openness += match cell.cell_type {
+                            CellType::TripleWord => 3.0,
+                            CellType::DoubleWord => 1.0,
+                            _ => 0.0,
+                        };
+                    }
+                }
+            }
+        }
+    }
+    openness
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum Persona {
+    // takes the highest score
+    #[default]
+    Standard,
+    // opens the board up looking for big scores
+    Aggressive,
+    // keeps the premium squares closed
+    Defensive,
+    // keeps good letters back for a bingo
+    BingoHunter,
+}
+
+impl Persona {
+    pub fn rate(&self, view: &GameView, game_move: &GameMove) -> f32 {
+        let score = game_move.score as f32;
+        let board = view.board();
+        match self {
+            Persona::Standard => score,
+            Persona::Aggressive => score + 4.0 * openness(board, &game_move.tile_positions(board)),
+            Persona::Defensive => score - 4.0 * openness(board, &game_move.tile_positions(board)),
+            Persona::BingoHunter => {
+                let leave = leave_after(&view.rack(), &game_move.tiles);
+                score + 2.0 * leave_value(&leave) + 3.0 * bingo_letters(&leave)
+            }
+        }
+    }
+}
+
+impl Display for Persona {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Persona::Standard => write!(f, "Standard"),
+            Persona::Aggressive => write!(f, "Aggressive"),
+            Persona::Defensive => write!(f, "Defensive"),
+            Persona::BingoHunter => write!(f, "Bingo hunter"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum MoveChoice {
+    // the best rated move
+    #[default]
+    Best,
+    // the k-th best rated move, 1 being the best
+    Rank(usize),
+    // the best rated move scoring in the band, or the closest to it
+    ScoreBand {
+        min: TScore,
+        max: TScore,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum BotLevel {
+    #[default]
+    Expert,
+    Intermediate,
+    Beginner,
+    Custom,
+}
+
+impl Display for BotLevel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BotLevel::Expert => write!(f, "Expert"),
+            BotLevel::Intermediate => write!(f, "Intermediate"),
+            BotLevel::Beginner => write!(f, "Beginner"),
+            BotLevel::Custom => write!(f, "Custom"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub struct BotConfig {
+    pub level: BotLevel,
+    pub persona: Persona,
+    // only play main words in this many of the most common words, if a frequency list is loaded
+    pub vocabulary: Option<usize>,
+    pub choice: MoveChoice,
+    // how long it can think about each move
+    pub time_limit: Option<Duration>,
+    // how long it can think for over the whole game
+    pub clock: Option<Duration>,
+}
+
+impl Display for BotConfig {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.level, self.persona)
+    }
+}
+
+impl BotConfig {
+    pub fn new(level: BotLevel, persona: Persona) -> Self {
+        let (vocabulary, choice, time_limit) = match level {
+            BotLevel::Expert | BotLevel::Custom => (None, MoveChoice::Best, None),
+            BotLevel::Intermediate => (Some(30000), MoveChoice::Rank(5), None),
+            BotLevel::Beginner => (
+                Some(5000),
+                MoveChoice::ScoreBand { min: 6, max: 16 },
+                Some(Duration::from_secs(2)),
+            ),
+        };
+        Self {
+            level,
+            persona,
+            vocabulary,
+            choice,
+            time_limit,
+            clock: None,
+        }
+    }
+
+    /*
+    How long to think about this move, the time limit or a share of what is left on the clock
+    if that is less.  The share allows for the turns still to come, roughly one for every eight
+    tiles in the bag plus a couple more to finish.
+    */
+    pub fn move_budget(&self, view: &GameView) -> Option<Duration> {
+        let share = self.clock.map(|clock| {
+            let turns_left = view.bag_count() as u32 / 8 + 2;
+            clock.saturating_sub(view.elapsed()) / turns_left
+        });
+        match (self.time_limit, share) {
+            (Some(limit), Some(share)) => Some(limit.min(share)),
+            (limit, share) => limit.or(share),
+        }
+    }
+
+    fn knows_word(&self, word: &str) -> bool {
+        match self.vocabulary {
+            Some(size) if has_frequency_list() => {
+                frequency_rank(word).is_some_and(|rank| rank <= size)
+            }
+            _ => true,
+        }
+    }
+
+    // the moves it knows the words for, best rated first
+    fn rate_moves(&self, view: &GameView, moves: Vec<GameMove>) -> Vec<(f32, GameMove)> {
+        let mut rated: Vec<(f32, GameMove)> = moves
+            .into_iter()
+            .filter(|m| self.knows_word(&m.word(view.board())))
+            .map(|m| (self.persona.rate(view, &m), m))
+            .collect();
+        rated.sort_by(|a, b| b.0.total_cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+        rated
+    }
+
+    // picks one of the moves for the current player, None if there is nothing it can play
+    pub fn choose_move(&self, view: &GameView, moves: Vec<GameMove>) -> Option<GameMove> {
+        self.pick(self.rate_moves(view, moves))
+    }
+
+    // picks one of the moves already rated, best first
+    fn pick(&self, rated: Vec<(f32, GameMove)>) -> Option<GameMove> {
+        match self.choice {
+            MoveChoice::Best => rated.into_iter().next(),
+            MoveChoice::Rank(k) => {
+                let index = k.saturating_sub(1).min(rated.len().saturating_sub(1));
+                rated.into_iter().nth(index)
+            }
+            MoveChoice::ScoreBand { min, max } => {
+                let distance = |score: TScore| (min - score).max(score - max).max(0);
+                rated.into_iter().min_by_key(|(_, m)| distance(m.score))
+            }
+        }
+        .map(|(_, m)| m)
+    }
+
+    /*
+    Looks ahead at the best rated moves, taking off the average of the opponent's best reply
+    from racks drawn from the unseen tiles, and keeps going round until the deadline.  Once the
+    bag is empty the opponent's rack is known, so one round is enough.  A round cut short by the
+    deadline is left out, and if not even one round is finished it goes on the rating alone.
+    */
+    pub fn simulate(
+        &self,
+        view: &GameView,
+        rated: Vec<(f32, GameMove)>,
+        deadline: Instant,
+    ) -> Option<GameMove> {
+        let mut candidates = rated;
+        candidates.truncate(SIMULATION_CANDIDATES);
+        let mut rng = rand::thread_rng();
+        let mut totals = vec![0.0f32; candidates.len()];
+        let mut rounds = 0;
+        while rounds < SIMULATION_ROUNDS && candidates.len() > 1 {
+            let replies: Vec<TScore> = candidates
+                .iter()
+                .map(|(_, m)| view.best_reply(m, &mut rng, Some(deadline)))
+                .collect();
+            if Instant::now() >= deadline {
+                break;
+            }
+            for (total, reply) in totals.iter_mut().zip(replies) {
+                *total += reply as f32;
+            }
+            rounds += 1;
+            if view.bag_count() == 0 {
+                break;
+            }
+        }
+        let value = |i: usize| candidates[i].0 - totals[i] / rounds.max(1) as f32;
+        (0..candidates.len())
+            .max_by(|&a, &b| value(a).total_cmp(&value(b)).then(b.cmp(&a)))
+            .map(|i| candidates.swap_remove(i).1)
+    }
+}
+
+impl Strategy for BotConfig {
+    fn name(&self) -> String {
+        self.to_string()
+    }
+
+    /*
+    Plays the chosen move unless changing some tiles is worth more, which is only weighed up when
+    playing the best move, weaker players only change tiles when they can't see a move.  With
+    nothing to play and too few tiles in the bag to change it passes.
+    With a time budget, half goes on finding moves, quickest first, and the rest on looking
+    ahead at the replies to the best of them.
+    */
+    fn choose_action(&self, view: &GameView) -> Action {
+        let start = Instant::now();
+        let budget = self.move_budget(view);
+        let moves = view.legal_moves(budget.map(|budget| start + budget / 2));
+        let rated = self.rate_moves(view, moves);
+        let exchange = match view.bag_count() >= 7 {
+            true => best_exchange(&view.rack()),
+            false => None,
+        };
+        /*
+        The exchange is weighed against every move found rather than the one picked, as the
+        look-ahead often settles on a quieter move that scores less, and a search cut short may
+        not have got as far as the best moves, both of which make changing tiles look better
+        than it is.
+        */
+        let rack = view.rack();
+        let best_equity = rated
+            .iter()
+            .map(|(_, m)| equity(&rack, m))
+            .max_by(f32::total_cmp);
+        if let (Some(exchange), Some(best_equity)) = (&exchange, best_equity) {
+            if self.choice == MoveChoice::Best && exchange.equity > best_equity {
+                return Action::Exchange(exchange.tiles.clone());
+            }
+        }
+        let game_move = match budget {
+            Some(budget) if self.choice == MoveChoice::Best => {
+                self.simulate(view, rated, start + budget)
+            }
+            _ => self.pick(rated),
+        };
+        match (game_move, exchange) {
+            (Some(game_move), _) => Action::Play(game_move),
+            (None, Some(exchange)) => Action::Exchange(exchange.tiles),
+            (None, None) => Action::Pass,
+        }
+    }
+}
+
+// the points scored plus what the tiles left on the rack are worth
+pub fn equity(rack: &TileBag, game_move: &GameMove) -> f32 {
+    game_move.score as f32 + leave_value(&leave_after(rack, &game_move.tiles))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Exchange {
+    pub keep: TileBag,
+    pub tiles: TileList,
+    // an exchange scores nothing, so this is just the value of the tiles kept
+    pub equity: f32,
+}
+
+// tries keeping every subset of the rack, changing at least one tile
+pub fn best_exchange(rack: &TileBag) -> Option<Exchange> {
+    let tiles: TileList = (*rack).into();
+    let mut seen = HashSet::new();
+    let mut best: Option<Exchange> = None;
+    for mask in 0u32..(1 << tiles.len()) - 1 {
+        let keep: TileBag = TileList(
+            (0..tiles.len())
+                .filter(|i| mask & (1 << i) != 0)
+                .map(|i| tiles.0[i])
+                .collect(),
+        )
+        .into();
+        if !seen.insert(keep) {
+            continue;
+        }
+        let equity = leave_value(&keep);
+        if best.as_ref().is_none_or(|b| equity > b.equity) {
+            best = Some(Exchange {
+                keep,
+                tiles: leave_after(rack, &keep.into()).into(),
+                equity,
+            });
+        }
+    }
+    best
+}
+
+// the tiles a move leaves on the rack
+pub fn leave_after(rack: &TileBag, tiles: &TileList) -> TileBag {
+    let mut leave = *rack;
+    leave.remove_tile_list(tiles);
+    leave
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::SCRABBLE_VARIANT_OFFICIAL;
+    use crate::notation::read_position;
+    use crate::tests::{set_rack, two_player_game};
+    use crate::tiles::Letter;
+    use crate::word_list::WordCheck;
+    use crate::Player;
+
+    #[test]
+    fn test_leave_value() {
+        let good: TileBag = TileList::try_from("ERS").unwrap().into();
+        let bad: TileBag = TileList::try_from("UUV").unwrap().into();
+        assert!(leave_value(&good) > leave_value(&bad));
+    }
+
+    #[test]
+    fn test_choose_move() {
+        let mut game = two_player_game(WordCheck::Exact, "AEINRST");
+        // the bag is reshuffled with a fixed seed, so the rack has to go in again
+        game.restart_with_seed(1);
+        set_rack(&mut game, 0, "AEINRST");
+        let moves = game.legal_moves(None);
+        assert!(!moves.is_empty());
+        let best = moves.iter().map(|m| m.score).max().unwrap();
+
+        let expert = BotConfig::new(BotLevel::Expert, Persona::Standard);
+        let chosen = expert.choose_move(&game.view(), moves.clone()).unwrap();
+        assert_eq!(chosen.score, best);
+
+        let band = BotConfig {
+            choice: MoveChoice::ScoreBand { min: 2, max: 4 },
+            ..expert
+        };
+        let chosen = band.choose_move(&game.view(), moves.clone()).unwrap();
+        assert!((2..=4).contains(&chosen.score), "{}", chosen.score);
+
+        game.player[0] = Player::new_computer(band);
+        game.computer_move();
+        assert_eq!(game.moves.len(), 1);
+    }
+
+    #[test]
+    fn test_exchange() {
+        let rack: TileBag = TileList::try_from("QVVUEST").unwrap().into();
+        let exchange = best_exchange(&rack).unwrap();
+        assert_eq!(exchange.keep.letters[Letter::from('S').as_usize()], 1);
+        assert_eq!(exchange.keep.letters[Letter::from('Q').as_usize()], 0);
+        assert!(exchange.keep.letters[Letter::from('V').as_usize()] < 2);
+        assert_eq!(
+            leave_after(&rack, &exchange.tiles),
+            exchange.keep,
+            "the tiles changed and the tiles kept make up the rack"
+        );
+
+        // nothing can be made from these, so they are changed while the bag is full enough
+        let game = two_player_game(WordCheck::Exact, "IIIUUUV");
+        let bot = BotConfig::default();
+        assert!(matches!(
+            bot.choose_action(&game.view()),
+            Action::Exchange(_)
+        ));
+        let position = "15/15/15/15/15/15/15/15/15/15/15/15/15/15/15 IIIUUUV/DEGOORU 0/0 EEE 1";
+        let game = read_position(position, &SCRABBLE_VARIANT_OFFICIAL, WordCheck::Exact).unwrap();
+        assert_eq!(bot.choose_action(&game.view()), Action::Pass);
+    }
+
+    #[test]
+    fn test_time_limit() {
+        // two blanks on an empty board take far longer than this to search fully
+        let game = two_player_game(WordCheck::Exact, "AEIRS??");
+        // a longer look-ahead mustn't talk it into changing a rack this good
+        for time_limit in [Duration::from_millis(300), Duration::from_secs(1)] {
+            let bot = BotConfig {
+                time_limit: Some(time_limit),
+                ..BotConfig::default()
+            };
+            let start = Instant::now();
+            let action = bot.choose_action(&game.view());
+            assert!(start.elapsed() < time_limit + Duration::from_secs(3));
+            assert!(
+                matches!(action, Action::Play(_)),
+                "{:?} {:?}",
+                time_limit,
+                action
+            );
+        }
+
+        let clock = BotConfig {
+            clock: Some(Duration::from_secs(60)),
+            ..BotConfig::default()
+        };
+        let budget = clock.move_budget(&game.view()).unwrap();
+        assert!(budget > Duration::ZERO && budget < Duration::from_secs(60));
+    }
+}
@@ -0,0 +1,542 @@
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use clap::{Parser, Subcommand, ValueEnum};
+use serde_json::json;
+
+use scrabble::analysis::{analyse_game, FoundMove};
+use scrabble::batch::{run_batch, BatchConfig, Contestant};
+use scrabble::board::{ScrabbleVariant, SCRABBLE_VARIANT_OFFICIAL, SCRABBLE_VARIANT_WORDFEUD};
+use scrabble::bot::{equity, BotConfig, BotLevel, Persona};
+use scrabble::gcg::{read_gcg, write_gcg};
+use scrabble::notation::{read_position, read_tiles};
+use scrabble::protocol::{run_engine, ExternalStrategy};
+use scrabble::statistics::game_statistics;
+use scrabble::strategy::Strategy;
+use scrabble::word_finder::{find_words, WordQuery};
+use scrabble::word_list::{is_word, WordCheck};
+use scrabble::{Game, Player, PlayerType};
+
+/*
+The commands for scripting, each with --json for output another program can read.  With no
+command the interactive menu runs as before.
+*/
+#[derive(Debug, Parser)]
+#[command(about = "Play Scrabble, find words and analyse games")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// The interactive menu
+    Menu,
+    /// Play a game, human players take their turns at the terminal
+    Play {
+        #[arg(long, value_enum, default_value_t = PlayerArg::Human)]
+        player1: PlayerArg,
+        #[arg(long, value_enum, default_value_t = PlayerArg::Expert)]
+        player2: PlayerArg,
+        #[arg(long, value_enum, default_value_t = VariantArg::Official)]
+        variant: VariantArg,
+        /// Fixes the tiles drawn
+        #[arg(long)]
+        seed: Option<u64>,
+        /// Save the game in GCG format
+        #[arg(long)]
+        gcg: Option<PathBuf>,
+        #[arg(long)]
+        json: bool,
+    },
+    /// The best plays in a position
+    Suggest {
+        /// A file holding a position, or just the board rows, see notation.rs
+        #[arg(long)]
+        board: Option<PathBuf>,
+        /// The rack to play from, ? for a blank
+        #[arg(long)]
+        rack: Option<String>,
+        #[arg(long, default_value_t = 10)]
+        count: usize,
+        /// Seconds to search for
+        #[arg(long)]
+        time: Option<u64>,
+        #[arg(long, value_enum, default_value_t = VariantArg::Official)]
+        variant: VariantArg,
+        #[arg(long)]
+        json: bool,
+    },
+    /// Whether each word is in the word list, exits with 1 if any isn't
+    Check {
+        #[arg(required = true)]
+        words: Vec<String>,
+        #[arg(long)]
+        json: bool,
+    },
+    /// The words using exactly these letters, ? for a blank
+    Anagram {
+        letters: String,
+        #[arg(long, value_enum, default_value_t = VariantArg::Official)]
+        variant: VariantArg,
+        #[arg(long)]
+        json: bool,
+    },
+    /// The better plays each player could have made in a GCG game
+    Analyze {
+        file: PathBuf,
+        /// Seconds to search each turn for
+        #[arg(long)]
+        time: Option<u64>,
+        #[arg(long, value_enum, default_value_t = VariantArg::Official)]
+        variant: VariantArg,
+        #[arg(long)]
+        json: bool,
+    },
+    /// A match between two computer players
+    Selfplay {
+        #[arg(long, default_value_t = 10)]
+        games: usize,
+        #[arg(long, default_value_t = 1)]
+        seed: u64,
+        #[arg(long)]
+        threads: Option<usize>,
+        #[arg(long, value_enum, default_value_t = PlayerArg::Intermediate)]
+        player1: PlayerArg,
+        #[arg(long, value_enum, default_value_t = PlayerArg::Intermediate)]
+        player2: PlayerArg,
+        /// A program speaking the engine protocol to play instead of player 1
+        #[arg(long)]
+        engine1: Option<String>,
+        /// A program speaking the engine protocol to play instead of player 2
+        #[arg(long)]
+        engine2: Option<String>,
+        #[arg(long, value_enum, default_value_t = VariantArg::Official)]
+        variant: VariantArg,
+        #[arg(long)]
+        json: bool,
+        #[arg(long)]
+        csv: bool,
+    },
+    /// Answer engine protocol requests on stdin, see protocol.rs
+    Engine {
+        #[arg(long, value_enum, default_value_t = PlayerArg::Expert)]
+        level: PlayerArg,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum PlayerArg {
+    Human,
+    Expert,
+    Intermediate,
+    Beginner,
+}
+
+impl PlayerArg {
+    fn bot(&self) -> Option<BotConfig> {
+        let level = match self {
+            PlayerArg::Human => return None,
+            PlayerArg::Expert => BotLevel::Expert,
+            PlayerArg::Intermediate => BotLevel::Intermediate,
+            PlayerArg::Beginner => BotLevel::Beginner,
+        };
+        Some(BotConfig::new(level, Persona::Standard))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum VariantArg {
+    Official,
+    Wordfeud,
+}
+
+impl VariantArg {
+    fn variant(&self) -> &'static ScrabbleVariant {
+        match self {
+            VariantArg::Official => &SCRABBLE_VARIANT_OFFICIAL,
+            VariantArg::Wordfeud => &SCRABBLE_VARIANT_WORDFEUD,
+        }
+    }
+}
+
+pub fn run(command: Command) -> ExitCode {
+    let result = match command {
+        Command::Menu => {
+            crate::menu_top();
+            Ok(())
+        }
+        Command::Play {
+            player1,
+            player2,
+            variant,
+            seed,
+            gcg,
+            json,
+        } => play([player1, player2], variant.variant(), seed, gcg, json),
+        Command::Suggest {
+            board,
+            rack,
+            count,
+            time,
+            variant,
+            json,
+        } => suggest(board, rack, count, time, variant.variant(), json),
+        Command::Check { words, json } => return check(&words, json),
+        Command::Anagram {
+            letters,
+            variant,
+            json,
+        } => anagram(&letters, variant.variant(), json),
+        Command::Analyze {
+            file,
+            time,
+            variant,
+            json,
+        } => analyze(file, time, variant.variant(), json),
+        Command::Selfplay {
+            games,
+            seed,
+            threads,
+            player1,
+            player2,
+            engine1,
+            engine2,
+            variant,
+            json,
+            csv,
+        } => selfplay(
+            games,
+            seed,
+            threads,
+            [(player1, engine1), (player2, engine2)],
+            variant.variant(),
+            json,
+            csv,
+        ),
+        Command::Engine { level } => {
+            let bot = level
+                .bot()
+                .ok_or("The engine must be a computer player".to_string());
+            bot.and_then(|bot| {
+                run_engine(io::stdin().lock(), io::stdout().lock(), bot).map_err(|e| e.to_string())
+            })
+        }
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn play(
+    players: [PlayerArg; 2],
+    scrabble_variant: &'static ScrabbleVariant,
+    seed: Option<u64>,
+    gcg: Option<PathBuf>,
+    json: bool,
+) -> Result<(), String> {
+    let mut game = Game::new(
+        scrabble_variant,
+        2,
+        std::array::from_fn(|i| match players.get(i).and_then(|p| p.bot()) {
+            Some(bot) => Player::new_computer(bot),
+            None => Player::new(PlayerType::Human),
+        }),
+        players
+            .iter()
+            .enumerate()
+            .map(|(i, p)| format!("Player {} ({:?})", i + 1, p))
+            .collect(),
+    );
+    if let Some(seed) = seed {
+        game.restart_with_seed(seed);
+    }
+    while !game.is_over {
+        match game.current_player().player_type {
+            PlayerType::Human => {
+                if crate::human_move(&mut game).is_err() {
+                    game.quit();
+                }
+            }
+            PlayerType::Computer => game.computer_move(),
+        }
+    }
+
+    if let Some(file) = gcg {
+        fs::write(&file, write_gcg(&game))
+            .map_err(|e| format!("Could not save to {}: {}", file.display(), e))?;
+    }
+    let stats = game_statistics(&game);
+    match json {
+        true => println!("{}", stats.to_json()),
+        false => println!("{}", stats),
+    }
+    Ok(())
+}
+
+fn suggest(
+    board: Option<PathBuf>,
+    rack: Option<String>,
+    count: usize,
+    time: Option<u64>,
+    scrabble_variant: &'static ScrabbleVariant,
+    json: bool,
+) -> Result<(), String> {
+    let position = match board {
+        Some(file) => fs::read_to_string(&file)
+            .map_err(|e| format!("Could not read {}: {}", file.display(), e))?,
+        None => vec!["15"; scrabble_variant.height as usize].join("/"),
+    };
+    let position = position.trim();
+    let rack = rack.map(|r| r.to_uppercase());
+    let mut game = match (position.contains(char::is_whitespace), &rack) {
+        (true, _) => read_position(position, scrabble_variant, WordCheck::Exact)?,
+        // just the board needs a rack to go with it
+        (false, Some(rack)) => read_position(
+            &format!("{} {}/- 0/0 1", position, rack),
+            scrabble_variant,
+            WordCheck::Exact,
+        )?,
+        (false, None) => return Err("A rack is needed with just a board".to_string()),
+    };
+    if let Some(rack) = rack {
+        game.current_player_mut().rack = read_tiles(&rack)?;
+    }
+
+    let rack = game.current_player().rack;
+    let mut moves = game.legal_moves(time.map(|t| Instant::now() + Duration::from_secs(t)));
+    moves.sort_by(|a, b| equity(&rack, b).total_cmp(&equity(&rack, a)));
+    let found: Vec<FoundMove> = moves
+        .iter()
+        .take(count)
+        .map(|m| FoundMove::new(&game, &rack, m))
+        .collect();
+    match json {
+        true => println!("{}", serde_json::to_string_pretty(&found).unwrap()),
+        false => {
+            for m in found.iter() {
+                println!("{:40} {:6.1} equity", m.to_string(), m.equity);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn check(words: &[String], json: bool) -> ExitCode {
+    let results: Vec<(String, bool)> = words
+        .iter()
+        .map(|w| (w.to_uppercase(), is_word(&w.to_uppercase())))
+        .collect();
+    match json {
+        true => {
+            let results: Vec<_> = results
+                .iter()
+                .map(|(word, valid)| json!({"word": word, "valid": valid}))
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&results).unwrap());
+        }
+        false => {
+            for (word, valid) in results.iter() {
+                println!("{} {}", word, if *valid { "valid" } else { "invalid" });
+            }
+        }
+    }
+    match results.iter().all(|(_, valid)| *valid) {
+        true => ExitCode::SUCCESS,
+        false => ExitCode::from(1),
+    }
+}
+
+fn anagram(
+    letters: &str,
+    scrabble_variant: &'static ScrabbleVariant,
+    json: bool,
+) -> Result<(), String> {
+    let query = WordQuery {
+        anagram: Some(letters.to_uppercase()),
+        ..WordQuery::default()
+    };
+    let matches = find_words(&query, scrabble_variant)?;
+    match json {
+        true => {
+            let matches: Vec<_> = matches
+                .iter()
+                .map(|m| json!({"word": m.word, "score": m.score}))
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&matches).unwrap());
+        }
+        false => {
+            for m in matches.iter() {
+                println!("{}", m);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn analyze(
+    file: PathBuf,
+    time: Option<u64>,
+    scrabble_variant: &'static ScrabbleVariant,
+    json: bool,
+) -> Result<(), String> {
+    let gcg = fs::read_to_string(&file)
+        .map_err(|e| format!("Could not read {}: {}", file.display(), e))?;
+    let game = read_gcg(&gcg, scrabble_variant, WordCheck::Exact)?;
+    let analysis = analyse_game(&game, time.map(Duration::from_secs));
+    match json {
+        true => println!("{}", analysis.to_json()),
+        false => println!("{}", analysis),
+    }
+    Ok(())
+}
+
+fn selfplay(
+    games: usize,
+    seed: u64,
+    threads: Option<usize>,
+    players: [(PlayerArg, Option<String>); 2],
+    scrabble_variant: &'static ScrabbleVariant,
+    json: bool,
+    csv: bool,
+) -> Result<(), String> {
+    let mut contestants = vec![];
+    let mut engines = vec![];
+    for (i, (player, engine)) in players.into_iter().enumerate() {
+        let strategy: Arc<dyn Strategy> = match engine {
+            Some(engine) => {
+                let engine = Arc::new(ExternalStrategy::new(&engine));
+                engines.push(engine.clone());
+                engine
+            }
+            None => Arc::new(
+                player
+                    .bot()
+                    .ok_or("Both players must be computer players".to_string())?,
+            ),
+        };
+        contestants.push(Contestant {
+            name: format!("Player {} ({})", i + 1, strategy.name()),
+            strategy,
+        });
+    }
+    let contestants: [Contestant; 2] = contestants.try_into().unwrap();
+    let mut config = BatchConfig::new(games, seed, scrabble_variant);
+    if let Some(threads) = threads {
+        config.threads = threads.max(1);
+    }
+    let report = run_batch(&contestants, &config);
+    for engine in engines.iter() {
+        for e in engine.take_errors() {
+            eprintln!("{} passed: {}", engine.name(), e);
+        }
+    }
+    match (json, csv) {
+        (true, _) => println!("{}", report.to_json()),
+        (false, true) => print!("{}", report.to_csv()),
+        (false, false) => println!("{}", report.summary),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let cli = Cli::try_parse_from(["scrabble"]).unwrap();
+        assert!(cli.command.is_none());
+
+        let cli = Cli::try_parse_from(["scrabble", "check", "QI", "ZZZ", "--json"]).unwrap();
+        match cli.command {
+            Some(Command::Check { words, json }) => {
+                assert_eq!(words, vec!["QI".to_string(), "ZZZ".to_string()]);
+                assert!(json);
+            }
+            command => panic!("expected check, got {:?}", command),
+        }
+
+        let cli = Cli::try_parse_from([
+            "scrabble",
+            "suggest",
+            "--rack",
+            "AEINRS?",
+            "--variant",
+            "wordfeud",
+        ])
+        .unwrap();
+        match cli.command {
+            Some(Command::Suggest {
+                rack,
+                count,
+                variant,
+                json,
+                ..
+            }) => {
+                assert_eq!(rack.as_deref(), Some("AEINRS?"));
+                assert_eq!((count, variant, json), (10, VariantArg::Wordfeud, false));
+            }
+            command => panic!("expected suggest, got {:?}", command),
+        }
+
+        let cli = Cli::try_parse_from([
+            "scrabble",
+            "selfplay",
+            "--games",
+            "4",
+            "--player2",
+            "beginner",
+            "--csv",
+        ])
+        .unwrap();
+        match cli.command {
+            Some(Command::Selfplay {
+                games,
+                player1,
+                player2,
+                json,
+                csv,
+                ..
+            }) => {
+                assert_eq!(games, 4);
+                assert_eq!(
+                    (player1, player2),
+                    (PlayerArg::Intermediate, PlayerArg::Beginner)
+                );
+                assert!(!json && csv);
+            }
+            command => panic!("expected selfplay, got {:?}", command),
+        }
+
+        let cli = Cli::try_parse_from(["scrabble", "analyze", "game.gcg", "--time", "2"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Command::Analyze {
+                time: Some(2),
+                json: false,
+                ..
+            })
+        ));
+        let cli = Cli::try_parse_from(["scrabble", "engine", "--level", "beginner"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Command::Engine {
+                level: PlayerArg::Beginner
+            })
+        ));
+
+        // a word to check is needed, and --json is only for the commands which take it
+        assert!(Cli::try_parse_from(["scrabble", "check"]).is_err());
+        assert!(Cli::try_parse_from(["scrabble", "engine", "--json"]).is_err());
+        assert!(Cli::try_parse_from(["scrabble", "play", "--player1", "grandmaster"]).is_err());
+    }
+}
@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::OnceLock;
+
+pub const DEFAULT_DEFINITIONS_FILE: &str = "definitions.txt";
+
+static DEFINITIONS: OnceLock<Definitions> = OnceLock::new();
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Definition {
+    pub word: String,
+    pub part_of_speech: String,
+    pub definition: String,
+    pub inflections: Vec<String>,
+}
+
+impl Display for Definition {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.word)?;
+        if !self.part_of_speech.is_empty() {
+            write!(f, " ({})", self.part_of_speech)?;
+        }
+        write!(f, " {}", self.definition)?;
+        if !self.inflections.is_empty() {
+            write!(f, " [{}]", self.inflections.join(" "))?;
+        }
+        Ok(())
+    }
+}
+
+/*
+Definitions read from a tab separated file, one word per line:
+    WORD    part of speech    definition    space separated inflections
+The inflections are also indexed, so CATS finds the entry for CAT.
+*/
+#[derive(Debug, Clone, Default)]
+pub struct Definitions {
+    entries: HashMap<String, Definition>,
+    inflections: HashMap<String, String>,
+}
+
+impl Definitions {
+    pub fn parse(contents: &str) -> Definitions {
+        let mut definitions = Definitions::default();
+        for line in contents.lines() {
+            let mut fields = line.split('\t').map(str::trim);
+            let word = match fields.next() {
+                Some(word) if !word.is_empty() && !word.starts_with('#') => word.to_uppercase(),
+                _ => continue,
+            };
+            let part_of_speech = fields.next().unwrap_or("").to_string();
+            let definition = fields.next().unwrap_or("").to_string();
+            let inflections: Vec<String> = fields
+                .next()
+                .unwrap_or("")
+                .split_whitespace()
+                .map(|w| w.to_uppercase())
+                .collect();
+            for inflection in inflections.iter() {
+                definitions
+                    .inflections
+                    .insert(inflection.clone(), word.clone());
+            }
+            definitions.entries.insert(
+                word.clone(),
+                Definition {
+                    word,
+                    part_of_speech,
+                    definition,
+                    inflections,
+                },
+            );
+        }
+        definitions
+    }
+
+    pub fn load(path: &Path) -> io::Result<Definitions> {
+        Ok(Self::parse(&fs::read_to_string(path)?))
+    }
+
+    pub fn get(&self, word: &str) -> Option<&Definition> {
+        self.entries.get(word).or_else(|| {
+            self.inflections
+                .get(word)
+                .and_then(|headword| self.entries.get(headword))
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+// loads the definitions used by `definition`, this can only be done once
+pub fn load_definitions(path: &Path) -> io::Result<usize> {
+    let definitions = Definitions::load(path)?;
+    let count = definitions.len();
+    DEFINITIONS
+        .set(definitions)
+        .map_err(|_| io::Error::new(io::ErrorKind::AlreadyExists, "definitions already loaded"))?;
+    Ok(count)
+}
+
+pub fn definition(word: &str) -> Option<&'static Definition> {
+    DEFINITIONS
+        .get()
+        .and_then(|definitions| definitions.get(word))
+}
+
+// the definition if we have one, otherwise a link to an online dictionary
+pub fn describe_word(word: &str) -> String {
+    match definition(word) {
+        Some(d) if d.word == word => d.to_string(),
+        Some(d) => format!("{} see {}", word, d),
+        None => format!(
+            "https://www.collinsdictionary.com/dictionary/english/{}",
+            word
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let definitions = Definitions::parse(
+            "# word\tpos\tdefinition\tinflections\n\
+             qi\tn\tthe vital force in the body\tQIS\n\
+             ZA\tn\tpizza\n",
+        );
+        assert_eq!(definitions.len(), 2);
+        let qi = definitions.get("QI").unwrap();
+        assert_eq!(qi.part_of_speech, "n");
+        assert_eq!(definitions.get("QIS"), Some(qi));
+        assert_eq!(definitions.get("ZA").unwrap().to_string(), "ZA (n) pizza");
+        assert!(definitions.get("ZAS").is_none());
+    }
+}
@@ -0,0 +1,406 @@
+use crate::board::{Board, ScrabbleVariant};
+use crate::notation::read_tiles;
+use crate::placement::Placement;
+use crate::pos::Position;
+use crate::tiles::{Letter, Tile, TileBag, TileList};
+use crate::word_list::WordCheck;
+use crate::{Direction, Game, GameMove, GameMoveRecord, GameMoveRecordDetail, Player, PlayerType};
+
+/*
+Games in the GCG format used by Quackle and the tournament software, e.g.
+
+  #player1 Ann Ann Smith
+  #player2 Bob Bob Jones
+  >Ann: AEINRST 8D RETAINS +72 72
+  >Bob: DEOORU? D8 .OgUE +12 12
+  >Ann: ABCDEFG -ABC +0 72
+  >Bob: DOR - +0 12
+
+The coordinate starts with the row for a word across and with the column for a word down, and
+'.' is a tile already on the board.  Blanks are '?' on a rack and lower case on the board.
+*/
+
+// e.g. 8D for a word across starting at D8, D8 for one going down
+pub fn write_coordinate(position: Position, direction: Direction) -> String {
+    let column = (b'A' + position.x) as char;
+    match direction {
+        Direction::Horizontal => format!("{}{}", position.y + 1, column),
+        Direction::Vertical => format!("{}{}", column, position.y + 1),
+    }
+}
+
+pub fn read_coordinate(
+    s: &str,
+    variant: &ScrabbleVariant,
+) -> Result<(Position, Direction), String> {
+    let invalid = || format!("Invalid coordinate: {}", s);
+    let s = s.to_uppercase();
+    let direction = match s.chars().next() {
+        Some('0'..='9') => Direction::Horizontal,
+        Some('A'..='Z') => Direction::Vertical,
+        _ => return Err(invalid()),
+    };
+    let row: String = s.chars().filter(|c| c.is_ascii_digit()).collect();
+    let column: String = s.chars().filter(|c| c.is_ascii_alphabetic()).collect();
+    if row.len() + column.len() != s.len() || column.len() != 1 {
+        return Err(invalid());
+    }
+    let x = column.as_bytes()[0] - b'A';
+    let y = row.parse::<u8>().map_err(|_| invalid())?;
+    if x >= variant.width || !(1..=variant.height).contains(&y) {
+        return Err(invalid());
+    }
+    Ok((Position::new(x, y - 1), direction))
+}
+
+// the word with a '.' for each tile already on the board
+fn write_word(board: &Board, game_move: &GameMove) -> String {
+    let mut word = String::new();
+    let mut tiles = game_move.tiles.0.iter();
+    let mut position = game_move.get_main_word_start_pos(board);
+    loop {
+        match board.get_cell_pos(position).as_filled() {
+            Some(_) => word.push('.'),
+            None => match tiles.next() {
+                Some(tile) => word.push_str(&tile.to_string()),
+                None => break,
+            },
+        }
+        match position.try_step_forward(game_move.direction) {
+            Some(next) => position = next,
+            None => break,
+        }
+    }
+    word
+}
+
+/*
+The tiles a word puts on the board and where each one goes.  A letter already on the board is
+a '.', or itself in brackets as in the usual notation, e.g. QU(I)Z.
+*/
+pub fn read_placements(
+    board: &Board,
+    start: Position,
+    direction: Direction,
+    word: &str,
+) -> Result<Vec<Placement>, String> {
+    let mut placements = vec![];
+    let mut position = Some(start);
+    let mut bracketed = false;
+    for c in word.chars() {
+        match c {
+            '(' if !bracketed => bracketed = true,
+            ')' if bracketed => bracketed = false,
+            _ => {
+                let Some(current) = position else {
+                    return Err(format!("{} goes off the board", word));
+                };
+                let on_board = board.get_cell_pos(current).as_filled();
+                match (c, on_board) {
+                    ('.', Some(_)) if !bracketed => {}
+                    (_, Some(letter))
+                        if bracketed && c.to_ascii_uppercase() == letter.as_char() => {}
+                    ('A'..='Z', None) if !bracketed => {
+                        placements.push(Placement::new(current, Tile::Letter(Letter::from(c))))
+                    }
+                    ('a'..='z', None) if !bracketed => placements.push(Placement::new(
+                        current,
+                        Tile::Blank {
+                            acting_as_letter: Some(Letter::from(c.to_ascii_uppercase())),
+                        },
+                    )),
+                    _ => return Err(format!("{} doesn't fit the board at {}", word, current)),
+                }
+                position = current.try_step_forward(direction);
+            }
+        }
+    }
+    if bracketed {
+        return Err(format!("{} is missing a closing bracket", word));
+    }
+    if placements.is_empty() {
+        return Err(format!("{} doesn't play any tiles", word));
+    }
+    Ok(placements)
+}
+
+// the tiles a word puts on the board, and where the first of them goes
+fn read_word(
+    board: &Board,
+    start: Position,
+    direction: Direction,
+    word: &str,
+) -> Result<(Position, TileList), String> {
+    let placements = read_placements(board, start, direction, word)?;
+    Ok((
+        placements[0].position,
+        TileList(placements.iter().map(|p| p.tile).collect()),
+    ))
+}
+
+// a move as it is written in a GCG file, e.g. 8D RE.AINS
+pub fn write_play(board: &Board, game_move: &GameMove) -> String {
+    format!(
+        "{} {}",
+        write_coordinate(
+            game_move.get_main_word_start_pos(board),
+            game_move.direction
+        ),
+        write_word(board, game_move)
+    )
+}
+
+// e.g. 8D QU(I)Z, the tiles of the play and where they go
+pub fn read_play_placements(board: &Board, s: &str) -> Result<Vec<Placement>, String> {
+    let (coordinate, word) = s
+        .trim()
+        .split_once(' ')
+        .ok_or(format!("Invalid play: {}", s))?;
+    let (start, direction) = read_coordinate(coordinate, board.scrabble_variant)?;
+    read_placements(board, start, direction, word.trim())
+}
+
+// the move is given no score, Game::validate_move works it out
+pub fn read_play(board: &Board, s: &str) -> Result<GameMove, String> {
+    let (coordinate, word) = s
+        .trim()
+        .split_once(' ')
+        .ok_or(format!("Invalid play: {}", s))?;
+    let (start, direction) = read_coordinate(coordinate, board.scrabble_variant)?;
+    let (starting_position, tiles) = read_word(board, start, direction, word.trim())?;
+    Ok(GameMove::new(starting_position, direction, tiles, 0))
+}
+
+fn nickname(name: &str) -> String {
+    name.replace(char::is_whitespace, "_")
+}
+
+pub fn write_gcg(game: &Game) -> String {
+    let mut gcg = "#character-encoding UTF-8\n".to_string();
+    for i in 0..game.number_of_players {
+        let name = &game.player_name[i];
+        gcg.push_str(&format!("#player{} {} {}\n", i + 1, nickname(name), name));
+    }
+    let mut totals = vec![0; game.number_of_players];
+    let mut replay = game.replay();
+    for record in game.moves.iter() {
+        replay.replay_turn(record);
+        let play = match &record.detail {
+            GameMoveRecordDetail::Move {
+                starting_position,
+                direction,
+                tiles,
+                score,
+                ..
+            } => {
+                let game_move =
+                    GameMove::new(*starting_position, *direction, tiles.clone(), *score);
+                totals[record.player] += *score;
+                format!("{} +{}", write_play(&replay.board, &game_move), score)
+            }
+            GameMoveRecordDetail::Exchange { tiles } => {
+                format!("-{} +0", tiles)
+            }
+            GameMoveRecordDetail::Pass => "- +0".to_string(),
+        };
+        gcg.push_str(&format!(
+            ">{}: {} {} {}\n",
+            nickname(&record.player_name),
+            record.rack,
+            play,
+            totals[record.player]
+        ));
+        replay.replay_record(record);
+    }
+    // the tiles left on the racks at the end
+    if game.is_over {
+        for (i, total) in totals.iter().enumerate() {
+            let player = &game.player[i];
+            if player.score != *total {
+                gcg.push_str(&format!(
+                    ">{}: ({}) {:+} {}\n",
+                    nickname(&game.player_name[i]),
+                    player.rack,
+                    player.score - total,
+                    player.score
+                ));
+            }
+        }
+    }
+    gcg
+}
+
+/*
+Sets up a game from a GCG file, with human players.  The racks are taken from the file and nothing
+is drawn, so the game can be replayed and analysed.  Challenged off phonies aren't supported, and
+the other kinds of line, e.g. the tiles left at the end, only change the scores.
+*/
+pub fn read_gcg(
+    gcg: &str,
+    scrabble_variant: &'static ScrabbleVariant,
+    word_check: WordCheck,
+) -> Result<Game, String> {
+    let mut nicknames = vec![];
+    let mut names = vec![];
+    for line in gcg.lines() {
+        if let Some(player) = line.strip_prefix("#player") {
+            let mut parts = player.split_whitespace().skip(1);
+            let nickname = parts.next().ok_or(format!("Invalid player: {}", line))?;
+            let name = parts.collect::<Vec<_>>().join(" ");
+            nicknames.push(nickname.to_string());
+            names.push(match name.is_empty() {
+                true => nickname.to_string(),
+                false => name,
+            });
+        }
+    }
+    if !(2..=4).contains(&nicknames.len()) {
+        return Err("A game needs between 2 and 4 players".to_string());
+    }
+    let mut game = Game::new_with_word_check(
+        scrabble_variant,
+        nicknames.len(),
+        std::array::from_fn(|_| Player::new(PlayerType::Human)),
+        names,
+        word_check,
+    );
+    game.bag = TileBag::new_empty();
+    let mut totals = vec![0; nicknames.len()];
+
+    for (number, line) in gcg.lines().enumerate() {
+        let Some(line) = line.strip_prefix('>') else {
+            continue;
+        };
+        let error = |e: String| format!("Line {}: {}", number + 1, e);
+        let (nickname, rest) = line
+            .split_once(':')
+            .ok_or_else(|| error("Invalid move".to_string()))?;
+        let player = nicknames
+            .iter()
+            .position(|n| n == nickname.trim())
+            .ok_or_else(|| error(format!("Unknown player {}", nickname)))?;
+        let fields: Vec<&str> = rest.split_whitespace().collect();
+        if let Some(total) = fields.last().and_then(|t| t.parse().ok()) {
+            totals[player] = total;
+        }
+        let detail = match fields[..] {
+            [_, "--", ..] => return Err(error("Withdrawn moves aren't supported".to_string())),
+            [_, "-", _, _] => GameMoveRecordDetail::Pass,
+            [_, exchange, _, _] if exchange.starts_with('-') => {
+                // the tiles changed can be hidden, e.g. -7
+                let tiles = match exchange[1..].parse::<u8>() {
+                    Ok(_) => TileList::new(),
+                    Err(_) => read_tiles(&exchange[1..]).map_err(error)?.into(),
+                };
+                GameMoveRecordDetail::Exchange { tiles }
+            }
+            [_, coordinate, word, score, _] => {
+                let (start, direction) =
+                    read_coordinate(coordinate, scrabble_variant).map_err(error)?;
+                let (starting_position, tiles) =
+                    read_word(&game.board, start, direction, word).map_err(error)?;
+                GameMoveRecordDetail::Move {
+                    starting_position,
+                    direction,
+                    tiles,
+                    score: score
+                        .parse()
+                        .map_err(|_| error(format!("Invalid score {}", score)))?,
+                    word: String::new(),
+                }
+            }
+            // challenges, time penalties and the tiles left at the end
+            _ => continue,
+        };
+        let rack = read_tiles(fields[0]).map_err(error)?;
+        if let GameMoveRecordDetail::Move { tiles, .. } = &detail {
+            rack.confirm_contains_tile_list(tiles)
+                .map_err(|_| error(format!("{} isn't on the rack {}", tiles, fields[0])))?;
+        }
+        let record = GameMoveRecord {
+            player,
+            player_name: game.player_name[player].clone(),
+            rack,
+            detail,
+        };
+        game.replay_turn(&record);
+        match record.detail {
+            GameMoveRecordDetail::Move { .. } => game.replay_record(&record),
+            _ => {
+                game.moves.push(record);
+                game.player[player].last_move = game.moves.len() - 1;
+            }
+        }
+    }
+
+    for (player, total) in game.player.iter_mut().zip(totals.iter()) {
+        player.score = *total;
+    }
+    let best = totals.iter().max().copied().unwrap_or(0);
+    let winners: Vec<usize> = (0..totals.len()).filter(|&i| totals[i] == best).collect();
+    game.winner = (winners.len() == 1).then(|| winners[0]);
+    game.is_over = true;
+    Ok(game)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::SCRABBLE_VARIANT_OFFICIAL;
+
+    #[test]
+    fn test_gcg() {
+        let gcg = "#player1 Ann Ann Smith\n\
+                   #player2 Bob Bob Jones\n\
+                   >Ann: AEINRST 8D RETAINS +72 72\n\
+                   >Bob: DEOORU? D8 .OgUE +12 12\n\
+                   >Ann: ABCDEFG -ABC +0 72\n\
+                   >Bob: DOR - +0 12\n";
+        let game = read_gcg(gcg, &SCRABBLE_VARIANT_OFFICIAL, WordCheck::Exact).unwrap();
+        assert_eq!(game.player_name, vec!["Ann Smith", "Bob Jones"]);
+        assert_eq!(game.moves.len(), 4);
+        assert_eq!((game.player[0].score, game.player[1].score), (72, 12));
+        assert_eq!(
+            game.board
+                .read_word_at_pos(Position::new(3, 7), Direction::Vertical),
+            "ROGUE"
+        );
+        match &game.moves[1].detail {
+            GameMoveRecordDetail::Move {
+                starting_position,
+                tiles,
+                ..
+            } => {
+                assert_eq!(*starting_position, Position::new(3, 8));
+                assert_eq!(tiles.to_string(), "OgUE");
+            }
+            _ => panic!("expected a move"),
+        }
+
+        let written = write_gcg(&game);
+        assert!(written.contains(">Ann_Smith: AEINRST 8D RETAINS +72 72\n"));
+        assert!(written.contains(">Bob_Jones: DEOORU? D8 .OgUE +12 12\n"));
+        let read = read_gcg(&written, &SCRABBLE_VARIANT_OFFICIAL, WordCheck::Exact).unwrap();
+        assert_eq!(read.board, game.board);
+        assert_eq!(read.moves.len(), 4);
+
+        assert_eq!(
+            read_coordinate("D8", &SCRABBLE_VARIANT_OFFICIAL),
+            Ok((Position::new(3, 7), Direction::Vertical))
+        );
+        assert!(read_coordinate("8P", &SCRABBLE_VARIANT_OFFICIAL).is_err());
+        assert!(read_coordinate("16A", &SCRABBLE_VARIANT_OFFICIAL).is_err());
+
+        // letters on the board can be in brackets instead of '.'
+        let placements = read_play_placements(&game.board, "9C A(O)").unwrap();
+        assert_eq!(
+            placements,
+            vec![Placement::new(
+                Position::new(2, 8),
+                Tile::Letter(Letter::from('A'))
+            )]
+        );
+        assert!(read_play_placements(&game.board, "9C A(X)").is_err());
+        assert!(read_play_placements(&game.board, "9C A(O").is_err());
+    }
+}
@@ -1,22 +1,43 @@
 use std::{
     fmt::{Display, Formatter},
     ops::Neg,
+    sync::Arc,
 };
 
 use board::{CellValue, MoveCell};
+use bot::BotConfig;
 use pos::Position;
+use rand::{rngs::StdRng, SeedableRng};
+use strategy::{Action, GameView, Strategy};
 
 use tiles::{Tile, TileBag, TileList, ALPHABET};
 use utils::Timer;
+use web_time::Instant;
 
-use crate::word_list::is_word;
+use crate::definitions::describe_word;
+use crate::word_list::{tag_word, WordCheck};
 //use word_list::{is_word, LETTER_PREFIXES, LETTER_SUFFIXES};
 
+pub mod analysis;
+pub mod batch;
 pub mod board;
+pub mod bot;
+pub mod definitions;
+pub mod gcg;
+pub mod strategy;
 pub mod tiles;
+pub mod training;
+pub mod word_finder;
 pub mod word_list;
 
+pub mod notation;
+pub mod placement;
 pub mod pos;
+pub mod profiles;
+pub mod protocol;
+pub mod puzzles;
+pub mod statistics;
+pub mod tournament;
 pub mod utils;
 
 pub type TScore = i16;
@@ -26,7 +47,7 @@ pub enum PlayerType {
     Human,
     Computer,
 }
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct Player {
     pub player_type: PlayerType,
     pub rack: tiles::TileBag,
@@ -35,6 +56,8 @@ pub struct Player {
     pub exchanges: u8,
     pub timer: Timer,
     pub last_move: usize,
+    // how a computer player chooses its moves, also used for suggestions to a human player
+    pub strategy: Arc<dyn Strategy>,
 }
 
 impl Player {
@@ -47,6 +70,18 @@ impl Player {
             exchanges: 0,
             timer: Timer::new(false),
             last_move: 0,
+            strategy: Arc::new(BotConfig::default()),
+        }
+    }
+
+    pub fn new_computer(bot: BotConfig) -> Self {
+        Self::with_strategy(Arc::new(bot))
+    }
+
+    pub fn with_strategy(strategy: Arc<dyn Strategy>) -> Self {
+        Self {
+            strategy,
+            ..Self::new(PlayerType::Computer)
         }
     }
 }
@@ -142,6 +177,52 @@ impl GameMove {
         }
         current_position
     }
+
+    // where each of the tiles will be placed, skipping over the filled cells
+    pub fn tile_positions(&self, board: &board::Board) -> Vec<Position> {
+        let mut positions = vec![];
+        let mut current_position = self.starting_position;
+        while positions.len() < self.tiles.len() {
+            if board.get_cell_pos(current_position).is_empty() {
+                positions.push(current_position);
+            }
+            match current_position.try_step_forward(self.direction) {
+                Some(next_position) => current_position = next_position,
+                None => break,
+            }
+        }
+        positions
+    }
+
+    // the main word the move makes, including the letters already on the board
+    pub fn word(&self, board: &board::Board) -> String {
+        let mut word = String::new();
+        let mut tiles = self.tiles.0.iter();
+        let mut current_position = self.get_main_word_start_pos(board);
+        loop {
+            match board.get_cell_pos(current_position).as_filled() {
+                Some(letter) => word.push(letter.as_char()),
+                None => match tiles.next().and_then(|tile| tile.letter()) {
+                    Some(letter) => word.push(letter.as_char()),
+                    None => break,
+                },
+            }
+            match current_position.try_step_forward(self.direction) {
+                Some(next_position) => current_position = next_position,
+                None => break,
+            }
+        }
+        word
+    }
+}
+
+// the fixed parameters of the search for moves from one starting position
+struct MoveSearch {
+    starting_position: Position,
+    direction: Direction,
+    min_tiles: u8,
+    max_tiles: u8,
+    deadline: Option<Instant>,
 }
 
 #[derive(Debug, Clone)]
@@ -151,6 +232,7 @@ pub enum GameMoveRecordDetail {
         direction: Direction,
         tiles: TileList,
         score: i16,
+        // as it is written down, with blanks in lower case
         word: String,
     },
     Exchange {
@@ -162,6 +244,8 @@ pub enum GameMoveRecordDetail {
 pub struct GameMoveRecord {
     pub player: usize,
     pub player_name: String,
+    // the rack before the move, so the game can be replayed and analysed
+    pub rack: TileBag,
     pub detail: GameMoveRecordDetail,
 }
 
@@ -169,7 +253,7 @@ impl Display for GameMoveRecordDetail {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             GameMoveRecordDetail::Move { score, word, .. } => {
-                write!(f, "{:3} points {}", score, word)
+                write!(f, "{:3} points {}", score, tag_word(word))
             }
             GameMoveRecordDetail::Exchange { tiles } => {
                 write!(f, "Exchange: tiles - {}", tiles)
@@ -198,11 +282,15 @@ pub struct Game {
     pub current_player: usize, // index into `players`
     pub first_move: bool,
     pub is_over: bool,
+    // ended by a player quitting rather than played out
+    pub was_quit: bool,
     pub last_player_to_play: Option<usize>,
     pub winner: Option<usize>,
     pub non_scoring_plays: u8,
     //  history of moves
     pub moves: Vec<GameMoveRecord>,
+    //  draws the tiles from the bag
+    rng: StdRng,
     //local_word_list: HashSet<String>,
 }
 
@@ -219,7 +307,7 @@ impl Display for Game {
                 self.player[i].timer.elapsed().as_secs_f64(),
                 if let Some(last_move) = self.moves.get(self.player[i].last_move) {
                     if let GameMoveRecordDetail::Move { word, .. } = &last_move.detail {
-                        recent_words.push(word.clone());
+                        recent_words.push(word.to_uppercase());
                     };
                     format!("last move {}", last_move)
                 } else {
@@ -228,15 +316,14 @@ impl Display for Game {
             )?;
         }
 
+        if self.word_check() != WordCheck::Exact {
+            writeln!(f, "{} rules", self.word_check())?;
+        }
         writeln!(f, "{}", self.board)?;
         writeln!(f, "Recent words:",)?;
 
         for word in recent_words.iter() {
-            writeln!(
-                f,
-                "https://www.collinsdictionary.com/dictionary/english/{}",
-                word
-            )?;
+            writeln!(f, "{}", describe_word(word))?;
         }
 
         writeln!(
@@ -261,11 +348,43 @@ pub enum MoveError {
     LetterNotAllowedInPosition,
     TilesNotInRack(Tile),
     BlankTileNotActingAsLetter,
+    // only a blank on its own can be exchanged
+    BlankTileActingAsLetter,
     NoTilesPassed,
     NotEnoughSpaceForTiles(u8),
     BlockingEmptyCellFound,
     NotEnoughTilesInBag,
     InvalidTile(char),
+    SquareFilled(Position),
+}
+
+impl Display for MoveError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MoveError::InvalidMove => write!(f, "That isn't a valid move"),
+            MoveError::InvalidWord(word) => write!(f, "{} isn't a word", word),
+            MoveError::InvalidPosition => write!(f, "That square isn't on the board"),
+            MoveError::InvalidDirection => write!(f, "The tiles must be in a line"),
+            MoveError::TilesDonNotFit => write!(f, "The tiles don't fit there"),
+            MoveError::TilesDoNotConnect => {
+                write!(f, "The tiles must join up with the ones on the board")
+            }
+            MoveError::LetterNotAllowedInPosition => {
+                write!(f, "A letter there doesn't make a word across it")
+            }
+            MoveError::TilesNotInRack(tile) => write!(f, "{} isn't on the rack", tile),
+            MoveError::BlankTileNotActingAsLetter => write!(f, "A blank must be given a letter"),
+            MoveError::BlankTileActingAsLetter => {
+                write!(f, "A blank can't be given a letter when it's exchanged")
+            }
+            MoveError::NoTilesPassed => write!(f, "No tiles were played"),
+            MoveError::NotEnoughSpaceForTiles(n) => write!(f, "There isn't room for {} tiles", n),
+            MoveError::BlockingEmptyCellFound => write!(f, "The tiles must be next to each other"),
+            MoveError::NotEnoughTilesInBag => write!(f, "There aren't enough tiles in the bag"),
+            MoveError::InvalidTile(c) => write!(f, "{} isn't a tile", c),
+            MoveError::SquareFilled(pos) => write!(f, "There is already a tile on {}", pos),
+        }
+    }
 }
 
 impl Game {
@@ -275,11 +394,28 @@ impl Game {
         players: [Player; 4],
         player_name: Vec<String>,
     ) -> Self {
-        is_word("the"); //  just to make sure the word list is loaded
+        Self::new_with_word_check(
+            scrabble_variant,
+            number_of_players,
+            players,
+            player_name,
+            WordCheck::Exact,
+        )
+    }
+
+    // a game with different rules for what counts as a word, e.g. Clabbers
+    pub fn new_with_word_check(
+        scrabble_variant: &'static board::ScrabbleVariant,
+        number_of_players: usize,
+        players: [Player; 4],
+        player_name: Vec<String>,
+        word_check: WordCheck,
+    ) -> Self {
+        word_check.is_valid("the"); //  just to make sure the word list is loaded
 
         let bag = tiles::TileBag::new(scrabble_variant);
 
-        let board = board::Board::new(scrabble_variant);
+        let board = board::Board::new(scrabble_variant, word_check);
         let next_player = 0;
         let moves = Vec::new();
         //let local_word_list = word_list::generate_anagrams(&players[1].rack);
@@ -293,15 +429,19 @@ impl Game {
             current_player: next_player,
             first_move: true,
             is_over: false,
+            was_quit: false,
             last_player_to_play: None,
             winner: None,
             non_scoring_plays: 0,
             moves,
+            rng: StdRng::from_entropy(),
             //  local_word_list,
         };
 
         for i in 0..number_of_players {
-            game.player[i].rack.fill_rack(&mut game.bag);
+            game.player[i]
+                .rack
+                .fill_rack_with_rng(&mut game.bag, &mut game.rng);
         }
 
         game.current_player_mut().timer.start();
@@ -310,9 +450,10 @@ impl Game {
 
     pub fn restart(&mut self) {
         self.bag = tiles::TileBag::new(self.scrabble_variant);
-        self.board = board::Board::new(self.scrabble_variant);
+        self.board = board::Board::new(self.scrabble_variant, self.board.word_check);
         self.first_move = true;
         self.is_over = false;
+        self.was_quit = false;
         self.last_player_to_play = None;
         self.winner = None;
         self.non_scoring_plays = 0;
@@ -322,7 +463,9 @@ impl Game {
             self.player[i].passes = 0;
             self.player[i].exchanges = 0;
             self.player[i].rack = TileBag::new_empty();
-            self.player[i].rack.fill_rack(&mut self.bag);
+            self.player[i]
+                .rack
+                .fill_rack_with_rng(&mut self.bag, &mut self.rng);
             self.player[i].timer = Timer::new(false);
             self.player[i].last_move = 0;
         }
@@ -330,6 +473,20 @@ impl Game {
         self.current_player_mut().timer.start();
     }
 
+    // starts again with the tiles drawn in an order fixed by the seed, so games can be replayed
+    pub fn restart_with_seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+        self.restart();
+    }
+
+    pub fn scrabble_variant(&self) -> &'static board::ScrabbleVariant {
+        self.scrabble_variant
+    }
+
+    pub fn word_check(&self) -> WordCheck {
+        self.board.word_check
+    }
+
     pub fn current_player(&self) -> &Player {
         &(self.player[self.current_player])
     }
@@ -423,7 +580,7 @@ impl Game {
                     }
                     let tile = tiles.0[tile_idx];
                     tile_idx += 1;
-                    let letter = tile.letter().unwrap();
+                    let letter = tile.try_letter()?;
                     word.push(letter.as_char());
                     let cell = self.board.get_cell_pos(current_pos);
                     let letter_multiplier = cell.cell_type.letter_multiplier();
@@ -451,7 +608,7 @@ impl Game {
             }
         }
         // check that the word is in the word list
-        if !is_word(&word) {
+        if !self.board.word_check.is_valid(&word) {
             return Err(MoveError::InvalidWord(word));
         }
         let score = main_word_score * word_multiplier as TScore
@@ -476,6 +633,7 @@ impl Game {
 
         let player = &mut self.player[self.current_player];
         //let player = self.current_player();
+        let rack = player.rack;
         let cross_direction = -direction;
 
         let mut tile_vec = tile_list.0.clone();
@@ -509,18 +667,21 @@ impl Game {
         self.moves.push(GameMoveRecord {
             player: self.current_player,
             player_name: self.player_name[self.current_player].clone(),
+            rack,
             detail: GameMoveRecordDetail::Move {
                 starting_position,
                 direction,
                 tiles: tile_list.clone(),
                 score,
-                word: self.board.read_word_at_pos(starting_position, direction),
+                word: self
+                    .board
+                    .read_played_word_at_pos(starting_position, direction),
             },
         });
 
         player.last_move = self.moves.len() - 1;
 
-        player.rack.fill_rack(&mut self.bag);
+        player.rack.fill_rack_with_rng(&mut self.bag, &mut self.rng);
         player.score += score;
 
         player.timer.stop();
@@ -542,17 +703,30 @@ impl Game {
             return Err(MoveError::NotEnoughTilesInBag);
         }
 
+        let has_letter = |tile: &Tile| {
+            matches!(
+                tile,
+                Tile::Blank {
+                    acting_as_letter: Some(_)
+                }
+            )
+        };
+        if tiles.0.iter().any(has_letter) {
+            return Err(MoveError::BlankTileActingAsLetter);
+        }
         let player_rack = &mut self.player[self.current_player].rack;
         player_rack.confirm_contains_tile_list(tiles)?;
+        let rack = *player_rack;
         player_rack.remove_tile_list(tiles);
         self.bag.add_tile_list(tiles);
-        player_rack.fill_rack(&mut self.bag);
+        player_rack.fill_rack_with_rng(&mut self.bag, &mut self.rng);
         self.player[self.current_player].exchanges += 1;
         self.non_scoring_plays += 1;
 
         self.moves.push(GameMoveRecord {
             player: self.current_player,
             player_name: self.player_name[self.current_player].clone(),
+            rack,
             detail: GameMoveRecordDetail::Exchange {
                 tiles: tiles.clone(),
             },
@@ -576,6 +750,7 @@ impl Game {
         self.moves.push(GameMoveRecord {
             player: self.current_player,
             player_name: self.player_name[self.current_player].clone(),
+            rack: self.current_player().rack,
             detail: GameMoveRecordDetail::Pass,
         });
 
@@ -594,6 +769,7 @@ impl Game {
     }
 
     pub fn quit(&mut self) {
+        self.was_quit = true;
         self.end_game();
     }
 
@@ -626,52 +802,47 @@ impl Game {
         Ok(())
     }
 
-    // recursive function to find the best move for a given position
+    // recursive function to find all the moves for a given position
     fn computer_move_position(
-        &mut self,
-        best_move: &mut GameMove, // the best move found so far, the contents will be updated if a better move is found
-        starting_position: Position,
-        direction: Direction,
-        min_tiles: u8,
-        max_tiles: u8,
+        &self,
+        moves: &mut Vec<GameMove>,
+        search: &MoveSearch,
         current_tile_list: TileList,
         current_rack: TileBag,
     ) {
-        if current_tile_list.len() as u8 >= max_tiles {
+        if current_tile_list.len() as u8 > search.max_tiles {
             return;
         }
-        if (current_tile_list.len() as u8) >= min_tiles {
-            //let try_move = &mut best_move.clone();
-            if let Ok(score) = self.validate_move(starting_position, direction, &current_tile_list)
-            {
-                if score > best_move.score {
-                    *best_move = GameMove::new(
-                        starting_position,
-                        direction,
-                        current_tile_list.clone(),
-                        score,
-                    );
-                }
+        if search
+            .deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            return;
+        }
+        if (current_tile_list.len() as u8) >= search.min_tiles {
+            if let Ok(score) = self.validate_move(
+                search.starting_position,
+                search.direction,
+                &current_tile_list,
+            ) {
+                moves.push(GameMove::new(
+                    search.starting_position,
+                    search.direction,
+                    current_tile_list.clone(),
+                    score,
+                ));
             }
         }
 
-        if (current_tile_list.len() as u8) < max_tiles && !current_rack.is_empty() {
+        if (current_tile_list.len() as u8) < search.max_tiles && !current_rack.is_empty() {
             for &letter in ALPHABET {
                 if current_rack.contains(letter) {
                     let mut new_tile_list = current_tile_list.clone();
                     let tile = Tile::Letter(letter);
                     new_tile_list.0.push(tile);
-                    let mut new_rack = current_rack.clone();
+                    let mut new_rack = current_rack;
                     new_rack.remove_letter(letter);
-                    self.computer_move_position(
-                        best_move,
-                        starting_position,
-                        direction,
-                        min_tiles,
-                        max_tiles,
-                        new_tile_list,
-                        new_rack,
-                    );
+                    self.computer_move_position(moves, search, new_tile_list, new_rack);
                 }
                 if current_rack.blanks > 0 {
                     let mut new_tile_list = current_tile_list.clone();
@@ -679,71 +850,93 @@ impl Game {
                         acting_as_letter: Some(letter),
                     };
                     new_tile_list.0.push(tile);
-                    let mut new_rack = current_rack.clone();
+                    let mut new_rack = current_rack;
                     new_rack.remove_blank();
-                    self.computer_move_position(
-                        best_move,
-                        starting_position,
-                        direction,
-                        min_tiles,
-                        max_tiles,
-                        new_tile_list,
-                        new_rack,
-                    );
+                    self.computer_move_position(moves, search, new_tile_list, new_rack);
                 }
             }
         }
     }
 
-    pub fn computer_move(&mut self) {
-        let start_pos = Position::new(7, 7);
-        let direction = Direction::Horizontal;
-        let best_move = &mut GameMove::new(start_pos, direction, TileList::new(), 0);
-
-        for &direction in [Direction::Vertical, Direction::Horizontal].iter() {
-            for y in 0..15 {
-                for x in 0..15 {
-                    let start_pos = Position::new(x, y);
-                    if let Ok((min_tiles, max_tiles)) = self.validate_position(start_pos, direction)
-                    {
-                        self.computer_move_position(
-                            best_move,
-                            start_pos,
-                            direction,
-                            min_tiles,
-                            max_tiles,
-                            TileList::new(),
-                            self.player[self.current_player].rack.clone(),
-                        );
+    /*
+    All the moves the current player can make, stopping early if the deadline passes.
+    The search is deepened a tile at a time, so when it is cut short it has still found every
+    move using fewer tiles, which is where the quick moves are.
+    Each move starts at the position of its first tile, and a single tile is only listed
+    horizontally when it makes words both ways, so the same move is only listed once.
+    */
+    pub fn legal_moves(&self, deadline: Option<Instant>) -> Vec<GameMove> {
+        let mut moves = vec![];
+        let rack = self.current_player().rack;
+
+        for depth in 1..=rack.count() {
+            if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                break;
+            }
+            for &direction in [Direction::Vertical, Direction::Horizontal].iter() {
+                for y in 0..15 {
+                    for x in 0..15 {
+                        let start_pos = Position::new(x, y);
+                        if !self.board.get_cell_pos(start_pos).is_empty() {
+                            continue;
+                        }
+                        if let Ok((min_tiles, max_tiles)) =
+                            self.validate_position(start_pos, direction)
+                        {
+                            if depth < min_tiles || depth > max_tiles {
+                                continue;
+                            }
+                            let search = MoveSearch {
+                                starting_position: start_pos,
+                                direction,
+                                min_tiles: depth,
+                                max_tiles: depth,
+                                deadline,
+                            };
+                            self.computer_move_position(&mut moves, &search, TileList::new(), rack);
+                        }
                     }
                 }
             }
         }
+        let single_tiles: Vec<(Position, TileList)> = moves
+            .iter()
+            .filter(|m| m.direction == Direction::Horizontal && m.tiles.len() == 1)
+            .map(|m| (m.starting_position, m.tiles.clone()))
+            .collect();
+        moves.retain(|m| {
+            m.direction == Direction::Horizontal
+                || m.tiles.len() > 1
+                || !single_tiles.contains(&(m.starting_position, m.tiles.clone()))
+        });
+        moves
+    }
 
-        if best_move.score == 0 {
-            self.computer_no_move().unwrap();
-        } else {
-            self.apply_move(
-                best_move.starting_position,
-                best_move.direction,
-                &best_move.tiles,
-                best_move.score,
-            )
-            .unwrap();
-        }
+    pub fn view(&self) -> GameView<'_> {
+        GameView::new(self)
     }
 
-    fn computer_no_move(&mut self) -> Result<(), MoveError> {
-        // if best move has score 0, then we haven't found a move, so we either pass or exchange tiles
-        // if we were the last player to play, we pass
-        // we can assume that someone can play, it is not possible that no-one can get a playable rack given we can exchange tiles
+    pub fn apply_action(&mut self, action: &Action) -> Result<(), MoveError> {
+        match action {
+            Action::Play(game_move) => self.human_move(
+                game_move.starting_position,
+                game_move.direction,
+                &game_move.tiles,
+            ),
+            Action::Exchange(tiles) => self.exchange_tiles(tiles),
+            Action::Pass => {
+                self.pass();
+                Ok(())
+            }
+        }
+    }
 
-        if self.bag.count() >= 7 {
-            let tiles = self.player[self.current_player].rack.into();
-            self.exchange_tiles(&tiles)?;
-            return Ok(());
-        } else {
-            return Ok(());
+    // lets the current player's strategy take its turn, an action it can't make is a pass
+    pub fn computer_move(&mut self) {
+        let strategy = self.current_player().strategy.clone();
+        let action = strategy.choose_action(&self.view());
+        if self.apply_action(&action).is_err() {
+            self.pass();
         }
     }
 
@@ -781,7 +974,113 @@ impl Game {
         self.winner = if draw { None } else { Some(winner) };
     }
 
+    // a copy of the game back at the start, nothing is drawn as the racks come from the record
+    pub(crate) fn replay(&self) -> Game {
+        let mut replay = Game::new_with_word_check(
+            self.scrabble_variant,
+            self.number_of_players,
+            self.player.clone(),
+            self.player_name.clone(),
+            self.word_check(),
+        );
+        replay.bag = TileBag::new_empty();
+        replay
+    }
+
+    // puts the replay at the start of the recorded turn
+    pub(crate) fn replay_turn(&mut self, record: &GameMoveRecord) {
+        self.current_player = record.player;
+        self.is_over = false;
+        self.player[record.player].rack = record.rack;
+    }
+
+    // plays the recorded turn on the replay, only moves change the board
+    pub(crate) fn replay_record(&mut self, record: &GameMoveRecord) {
+        self.replay_turn(record);
+        if let GameMoveRecordDetail::Move {
+            starting_position,
+            direction,
+            tiles,
+            score,
+            ..
+        } = &record.detail
+        {
+            let _ = self.apply_move(*starting_position, *direction, tiles, *score);
+        }
+    }
+
     pub fn last_move(&self) -> Option<&GameMoveRecord> {
         self.moves.last()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::SCRABBLE_VARIANT_OFFICIAL;
+
+    // gives a player these tiles, putting the ones they had back in the bag and taking these out
+    pub(crate) fn set_rack(game: &mut Game, player: usize, rack: &str) {
+        let tiles = TileList::try_from(rack).unwrap();
+        game.bag.add_tile_list(&game.player[player].rack.into());
+        game.bag.remove_tile_list(&tiles);
+        game.player[player].rack = tiles.into();
+    }
+
+    pub(crate) fn two_player_game(word_check: WordCheck, rack: &str) -> Game {
+        let mut game = Game::new_with_word_check(
+            &SCRABBLE_VARIANT_OFFICIAL,
+            2,
+            std::array::from_fn(|_| Player::new(PlayerType::Human)),
+            vec!["Player 1".to_string(), "Player 2".to_string()],
+            word_check,
+        );
+        // the other rack goes back first, so it can't be holding a tile this one needs
+        game.bag.add_tile_list(&game.player[1].rack.into());
+        game.player[1].rack = TileBag::new_empty();
+        set_rack(&mut game, 0, rack);
+        game.player[1].rack.fill_rack(&mut game.bag);
+        game
+    }
+
+    #[test]
+    fn test_clabbers_move() {
+        let tiles = TileList::try_from("TCA").unwrap();
+        let start = Position::new(7, 7);
+
+        let mut game = two_player_game(WordCheck::Exact, "TCAEIRS");
+        assert_eq!(
+            game.human_move(start, Direction::Horizontal, &tiles),
+            Err(MoveError::InvalidWord("TCA".to_string()))
+        );
+
+        let mut game = two_player_game(WordCheck::Clabbers, "TCAEIRS");
+        assert_eq!(
+            game.human_move(start, Direction::Horizontal, &tiles),
+            Ok(())
+        );
+        assert_eq!(game.player[0].score, 10);
+    }
+
+    #[test]
+    fn test_unassigned_blank() {
+        let mut game = two_player_game(WordCheck::Exact, "RETAIN?");
+        let start = Position::new(7, 7);
+        assert_eq!(
+            game.human_move(
+                start,
+                Direction::Horizontal,
+                &TileList::try_from("RETAIN?").unwrap()
+            ),
+            Err(MoveError::BlankTileNotActingAsLetter)
+        );
+        assert_eq!(
+            game.exchange_tiles(&TileList::try_from("Rs").unwrap()),
+            Err(MoveError::BlankTileActingAsLetter)
+        );
+        assert_eq!(
+            game.exchange_tiles(&TileList::try_from("R?").unwrap()),
+            Ok(())
+        );
+    }
+}
@@ -1,23 +1,82 @@
+mod cli;
+
+use std::fs;
 use std::io;
+use std::path::Path;
+use std::process::ExitCode;
 use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
 
+use clap::Parser;
 use itertools::Itertools;
 use scrabble::board::SCRABBLE_VARIANT_OFFICIAL;
 use scrabble::board::SCRABBLE_VARIANT_WORDFEUD;
 use scrabble::*;
 
+use cli::{Cli, Command};
+use scrabble::analysis::analyse_game;
+use scrabble::batch::{run_batch, BatchConfig, Contestant};
 use scrabble::board::ScrabbleVariant;
+use scrabble::bot::{BotConfig, BotLevel, MoveChoice, Persona};
+use scrabble::definitions::{describe_word, load_definitions, DEFAULT_DEFINITIONS_FILE};
+use scrabble::gcg::{read_gcg, read_play_placements, write_gcg};
+use scrabble::notation::{read_position, write_position};
+use scrabble::placement::placed_move;
 use scrabble::pos::Position;
+use scrabble::profiles::{Profiles, DEFAULT_PROFILES_FILE};
+use scrabble::protocol::ExternalStrategy;
+use scrabble::puzzles::{
+    load_puzzles, puzzles_from_game, puzzles_from_position, save_puzzles, self_play_puzzles,
+    Puzzle, PuzzleKind, DEFAULT_PUZZLES_FILE,
+};
+use scrabble::statistics::game_statistics;
+use scrabble::strategy::{Action, Strategy};
 use scrabble::tiles::TileList;
-use scrabble::word_list::is_word;
+use scrabble::tournament::{Entrant, Format, GameScore, Tournament, DEFAULT_TOURNAMENT_FILE};
+use scrabble::training::{
+    self, build_quiz, Progress, QuizKind, QuizSpec, QuizSummary, DEFAULT_PROGRESS_FILE,
+};
+use scrabble::utils::Timer;
+use scrabble::word_finder::{self, WordQuery};
+use scrabble::word_list::{
+    compare_lexicons, comparison_lexicon, has_frequency_list, is_word, load_comparison_lexicon,
+    load_frequency_list, tag_word, Lexicon, WordCheck, DEFAULT_COMPARISON_LEXICON_FILE,
+    DEFAULT_FREQUENCY_FILE,
+};
 
 //TO DO : use terminal escape codes to clear the screen
 //https://stackoverflow.com/questions/2979383/c-clear-the-console
 //https://stackoverflow.com/questions/4842424/list-of-ansi-color-escape-sequences
 //https://stackoverflow.com/questions/2616906/how-do-i-output-coloured-text-to-a-linux-terminal
 
-fn main() {
-    menu_top();
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let definitions_file = std::env::var("SCRABBLE_DEFINITIONS")
+        .unwrap_or_else(|_| DEFAULT_DEFINITIONS_FILE.to_string());
+    if Path::new(&definitions_file).exists() {
+        if let Err(e) = load_definitions(Path::new(&definitions_file)) {
+            eprintln!(
+                "Could not read definitions from {}: {}",
+                definitions_file, e
+            );
+        }
+    }
+    let comparison_file = std::env::var("SCRABBLE_COMPARISON_LEXICON")
+        .unwrap_or_else(|_| DEFAULT_COMPARISON_LEXICON_FILE.to_string());
+    if Path::new(&comparison_file).exists() {
+        if let Err(e) = load_comparison_lexicon(Path::new(&comparison_file)) {
+            eprintln!("Could not read word list {}: {}", comparison_file, e);
+        }
+    }
+    let frequency_file = std::env::var("SCRABBLE_FREQUENCY_LIST")
+        .unwrap_or_else(|_| DEFAULT_FREQUENCY_FILE.to_string());
+    if Path::new(&frequency_file).exists() {
+        if let Err(e) = load_frequency_list(Path::new(&frequency_file)) {
+            eprintln!("Could not read word frequencies {}: {}", frequency_file, e);
+        }
+    }
+    cli::run(cli.command.unwrap_or(Command::Menu))
 }
 
 fn menu_top() {
@@ -32,7 +91,14 @@ fn menu_top() {
         if game.is_some() {
             println!("5) Restart game");
         }
+        println!("6) Compare word lists");
+        println!("7) Find words");
+        println!("8) Word study");
         println!("9) Look up word");
+        println!("10) Computer match");
+        println!("11) Player ratings");
+        println!("12) Club tournament");
+        println!("13) Puzzles");
         println!("0) Exit");
 
         // read a char from stdin and compare to  1 to 4
@@ -64,8 +130,28 @@ fn menu_top() {
                     g.restart();
                 }
             }
-
+            "6" => {
+                let _ = compare_word_lists();
+            }
+            "7" => {
+                let _ = find_words();
+            }
+            "8" => {
+                let _ = word_study();
+            }
             "9" => look_up_word(),
+            "10" => {
+                let _ = computer_match();
+            }
+            "11" => {
+                let _ = show_ratings();
+            }
+            "12" => {
+                let _ = club_tournament();
+            }
+            "13" => {
+                let _ = puzzles();
+            }
             "0" => break,
             _ => {
                 println!("Invalid input");
@@ -107,85 +193,572 @@ fn play_game(game: &mut Game) -> Result<(), UserCancelError> {
         .rev()
         .for_each(|(score, name)| println!("{} scored {}", name, score));
 
+    println!("\n{}", game_statistics(game));
+    record_ratings(game);
+
+    if get_user_input_bool("Analyse the game?", "N", true)? {
+        analyse(game)?;
+    }
+
     get_user_input_string_uppercase("Press enter to continue...", "", false)?;
 
     Ok(())
 }
 
+// shows the better moves each player could have made
+fn analyse(game: &Game) -> Result<(), UserCancelError> {
+    let seconds = get_user_input_integer(
+        "How many seconds can the analysis spend on each turn? (0 for no limit)",
+        "5",
+        0,
+        600,
+        true,
+    )?;
+    let time_per_turn = (seconds > 0).then(|| Duration::from_secs(seconds as u64));
+    let analysis = analyse_game(game, time_per_turn);
+    println!("{}", analysis);
+
+    let file = get_user_input_string("Save the analysis to a .json file (- for none)", "-", true)?;
+    if file != "-" {
+        match fs::write(&file, analysis.to_json()) {
+            Ok(()) => println!("Saved to {}", file),
+            Err(e) => println!("Could not save to {}: {}", file, e),
+        }
+    }
+    Ok(())
+}
+
 fn ad_hoc_game() -> Result<Game, UserCancelError> {
     let scrabble_variant = get_user_input_scrabble_variant()?;
+    let word_check = get_user_input_word_check()?;
+    let position = get_user_input_string("Start from a position (- for a new game)", "-", true)?;
+    if position != "-" {
+        return position_game(&position, scrabble_variant, word_check);
+    }
     let number_of_players =
         get_user_input_integer("Enter number of players", "2", 2, 4, true)? as usize;
-    let mut players = [Player::new(PlayerType::Human); 4];
+    let mut players = std::array::from_fn(|_| Player::new(PlayerType::Human));
     let mut player_name: Vec<String> = vec![];
 
     for i in 0..number_of_players {
-        let player_type = get_user_input_player_type()?;
-        let name = get_user_player_name(i)?;
+        let player = get_user_input_player()?;
+        let name = get_user_input_profile(
+            &format!("Enter name for player {}", i),
+            &format!("Player {}", i),
+        )?;
 
-        players[i as usize] = Player::new(player_type);
+        players[i] = player;
         player_name.push(name);
     }
 
-    let mut game = Game::new(&scrabble_variant, number_of_players, players, player_name);
+    let mut game = Game::new_with_word_check(
+        scrabble_variant,
+        number_of_players,
+        players,
+        player_name,
+        word_check,
+    );
+    play_game(&mut game)?;
+    Ok(game)
+}
+
+// plays on from a position written in the notation::write_position format
+fn position_game(
+    position: &str,
+    scrabble_variant: &'static ScrabbleVariant,
+    word_check: WordCheck,
+) -> Result<Game, UserCancelError> {
+    let mut game = match read_position(position, scrabble_variant, word_check) {
+        Ok(game) => game,
+        Err(e) => {
+            println!("{}", e);
+            get_user_input_string_uppercase("Press enter to continue...", "", false)?;
+            return Err(UserCancelError::UserCancelled);
+        }
+    };
+    for i in 0..game.number_of_players {
+        let player = get_user_input_player()?;
+        let name = get_user_input_profile(
+            &format!("Enter name for player {}", i + 1),
+            &format!("Player {}", i + 1),
+        )?;
+        let old = &game.player[i];
+        game.player[i] = Player {
+            rack: old.rack,
+            score: old.score,
+            timer: old.timer,
+            ..player
+        };
+        game.player_name[i] = name;
+    }
     play_game(&mut game)?;
     Ok(game)
 }
 
 fn computer_vs_computer() -> Result<Game, UserCancelError> {
     let scrabble_variant = get_user_input_scrabble_variant()?;
+    let word_check = get_user_input_word_check()?;
     let number_of_players = 2;
-    let mut players = [Player::new(PlayerType::Human); 4];
+    let mut players = std::array::from_fn(|_| Player::new(PlayerType::Human));
     let mut player_name: Vec<String> = vec![];
 
     for i in 0..number_of_players {
-        let player_type = PlayerType::Computer;
         let name = format!("Player {}", i + 1);
+        println!("{}", name);
+        let bot = get_user_input_bot_config()?;
 
-        players[i as usize] = Player::new(player_type);
-        player_name.push(name);
+        players[i] = Player::new_computer(bot);
+        player_name.push(format!("{} ({})", name, bot));
     }
 
-    let mut game = Game::new(&scrabble_variant, number_of_players, players, player_name);
+    let mut game = Game::new_with_word_check(
+        scrabble_variant,
+        number_of_players,
+        players,
+        player_name,
+        word_check,
+    );
 
     play_game(&mut game)?;
     Ok(game)
 }
 
+// plays a batch of games between two computer players without showing them
+fn computer_match() -> Result<(), UserCancelError> {
+    let scrabble_variant = get_user_input_scrabble_variant()?;
+    let word_check = get_user_input_word_check()?;
+    let mut contestants = vec![];
+    for i in 0..2 {
+        println!("Player {}", i + 1);
+        let bot = get_user_input_bot_config()?;
+        contestants.push(Contestant {
+            name: format!("Player {} ({})", i + 1, bot),
+            strategy: Arc::new(bot),
+        });
+    }
+    let contestants: [Contestant; 2] = contestants.try_into().unwrap();
+    let games = get_user_input_integer("How many games?", "10", 1, 100_000, true)? as usize;
+    let seed = get_user_input_integer("Seed for the tiles drawn", "1", 1, i32::MAX, true)? as u64;
+    let file = get_user_input_string(
+        "Save the results to a .csv or .json file (- for none)",
+        "-",
+        true,
+    )?;
+    let config = BatchConfig {
+        word_check,
+        ..BatchConfig::new(games, seed, scrabble_variant)
+    };
+
+    println!("Playing {} games on {} threads...", games, config.threads);
+    let report = run_batch(&contestants, &config);
+    println!("{}", report.summary);
+
+    if file != "-" {
+        let contents = match Path::new(&file).extension().and_then(|e| e.to_str()) {
+            Some("json") => report.to_json(),
+            _ => report.to_csv(),
+        };
+        match fs::write(&file, contents) {
+            Ok(()) => println!("Saved to {}", file),
+            Err(e) => println!("Could not save to {}: {}", file, e),
+        }
+    }
+    get_user_input_string_uppercase("Press enter to continue...", "", false)?;
+    Ok(())
+}
+
+// runs the tournament kept in tournament.json, starting a new one if there isn't one
+fn club_tournament() -> Result<(), UserCancelError> {
+    let path = Path::new(DEFAULT_TOURNAMENT_FILE);
+    let mut tournament = match Tournament::load(path) {
+        Ok(t)
+            if !t.is_finished() || !get_user_input_bool("Start a new tournament?", "N", true)? =>
+        {
+            t
+        }
+        Ok(_) => new_tournament()?,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => new_tournament()?,
+        Err(e) => {
+            println!("Could not read {}: {}", DEFAULT_TOURNAMENT_FILE, e);
+            new_tournament()?
+        }
+    };
+    loop {
+        println!("\n{}", tournament);
+        let input = get_user_input_string_uppercase(
+            "1) Pair the next round\n2) Play or enter results\n3) Show pairings\n4) Export the standings\n5) New tournament",
+            "2",
+            true,
+        )?;
+        match input.as_str() {
+            "1" => match tournament.pair_next_round() {
+                Ok(_) => show_pairings(&tournament),
+                Err(e) => println!("{}", e),
+            },
+            "2" => tournament_results(&mut tournament, path)?,
+            "3" => show_pairings(&tournament),
+            "4" => {
+                let file = get_user_input_string("Save the standings to", "standings.csv", true)?;
+                match fs::write(&file, tournament.standings_csv()) {
+                    Ok(()) => println!("Saved to {}", file),
+                    Err(e) => println!("Could not save to {}: {}", file, e),
+                }
+            }
+            "5" => tournament = new_tournament()?,
+            _ => println!("Please enter a number between 1 and 5"),
+        }
+        save_tournament(&tournament, path);
+    }
+}
+
+fn save_tournament(tournament: &Tournament, path: &Path) {
+    if let Err(e) = tournament.save(path) {
+        println!("Could not save {}: {}", path.display(), e);
+    }
+}
+
+fn new_tournament() -> Result<Tournament, UserCancelError> {
+    let name = get_user_input_string("Name of the tournament", "Club tournament", true)?;
+    let number = get_user_input_integer("How many players?", "4", 2, 64, true)? as usize;
+    let mut entrants = vec![];
+    for i in 0..number {
+        let player_name = get_user_input_profile(
+            &format!("Enter name for player {}", i + 1),
+            &format!("Player {}", i + 1),
+        )?;
+        let bot = match get_user_input_bool("Is this a computer player?", "N", true)? {
+            true => Some(get_user_input_bot_config()?),
+            false => None,
+        };
+        let engine =
+            match bot.is_some() && get_user_input_bool("Is it an external engine?", "N", true)? {
+                true => Some(get_user_input_string(
+                    "Command to run the engine",
+                    "scrabble engine",
+                    true,
+                )?),
+                false => None,
+            };
+        entrants.push(Entrant {
+            name: player_name,
+            bot,
+            engine,
+        });
+    }
+    let format = match get_user_input_bool("Round robin? (N for Swiss)", "Y", true)? {
+        true => Format::RoundRobin,
+        false => Format::Swiss {
+            rounds: get_user_input_integer("How many rounds?", "4", 1, number as i32, true)?
+                as usize,
+        },
+    };
+    match Tournament::new(&name, format, entrants) {
+        Ok(tournament) => Ok(tournament),
+        Err(e) => {
+            println!("{}", e);
+            new_tournament()
+        }
+    }
+}
+
+fn show_pairings(tournament: &Tournament) {
+    for (round, pairings) in tournament.rounds.iter().enumerate() {
+        println!("Round {}", round + 1);
+        for (i, p) in pairings.iter().enumerate() {
+            let first = &tournament.entrants[p.first].name;
+            match (p.second, &p.score) {
+                (None, _) => println!("  {}) {} has a bye", i + 1, first),
+                (Some(second), Some(score)) => println!(
+                    "  {}) {} {} - {} {}",
+                    i + 1,
+                    first,
+                    score.first,
+                    score.second,
+                    tournament.entrants[second].name
+                ),
+                (Some(second), None) => println!(
+                    "  {}) {} v {}",
+                    i + 1,
+                    first,
+                    tournament.entrants[second].name
+                ),
+            }
+        }
+    }
+}
+
+/*
+Goes through the unfinished games of the current round, computer players play each other straight
+away and games with a human are played here or have their scores entered.
+*/
+// each result is saved as it's recorded, so none are lost if the rest of the round is left
+fn tournament_results(tournament: &mut Tournament, path: &Path) -> Result<(), UserCancelError> {
+    let Some(round) = tournament.rounds.len().checked_sub(1) else {
+        println!("Pair the first round first");
+        return Ok(());
+    };
+    for i in 0..tournament.rounds[round].len() {
+        let pairing = tournament.rounds[round][i].clone();
+        let Some(second) = pairing.second else {
+            continue;
+        };
+        if pairing.score.is_some() {
+            continue;
+        }
+        let entrants = [
+            tournament.entrants[pairing.first].clone(),
+            tournament.entrants[second].clone(),
+        ];
+        println!("{} v {}", entrants[0].name, entrants[1].name);
+        let bots = entrants.iter().all(|e| e.bot.is_some());
+        let score = if bots || get_user_input_bool("Play the game now?", "Y", true)? {
+            let mut players = std::array::from_fn(|_| Player::new(PlayerType::Human));
+            let mut engines = vec![];
+            for (player, entrant) in players.iter_mut().zip(entrants.iter()) {
+                if let Some(engine) = &entrant.engine {
+                    let engine = Arc::new(ExternalStrategy::new(engine));
+                    *player = Player::with_strategy(engine.clone());
+                    engines.push(engine);
+                } else if let Some(bot) = entrant.bot {
+                    *player = Player::new_computer(bot);
+                }
+            }
+            let mut game = Game::new(
+                &SCRABBLE_VARIANT_OFFICIAL,
+                2,
+                players,
+                entrants.iter().map(|e| e.name.clone()).collect(),
+            );
+            match bots {
+                true => {
+                    while !game.is_over {
+                        game.computer_move();
+                    }
+                    println!("{}", game);
+                }
+                false => play_game(&mut game)?,
+            }
+            for engine in engines.iter() {
+                for e in engine.take_errors() {
+                    println!("{} passed: {}", engine.name(), e);
+                }
+            }
+            let file = format!("tournament-r{}-g{}.gcg", round + 1, i + 1);
+            let game_file = match fs::write(&file, write_gcg(&game)) {
+                Ok(()) => Some(file),
+                Err(e) => {
+                    println!("Could not save to {}: {}", file, e);
+                    None
+                }
+            };
+            GameScore {
+                first: game.player[0].score,
+                second: game.player[1].score,
+                game_file,
+            }
+        } else {
+            let first = get_user_input_integer(
+                &format!("Score for {}", entrants[0].name),
+                "0",
+                -1000,
+                10_000,
+                false,
+            )?;
+            let second = get_user_input_integer(
+                &format!("Score for {}", entrants[1].name),
+                "0",
+                -1000,
+                10_000,
+                false,
+            )?;
+            let file = get_user_input_string("File with the game record (- for none)", "-", true)?;
+            GameScore {
+                first: first as TScore,
+                second: second as TScore,
+                game_file: (file != "-").then_some(file),
+            }
+        };
+        println!(
+            "{} {} - {} {}",
+            entrants[0].name, score.first, score.second, entrants[1].name
+        );
+        match tournament.record_result(round, i, score) {
+            Ok(()) => save_tournament(tournament, path),
+            Err(e) => println!("{}", e),
+        }
+    }
+    Ok(())
+}
+
+// makes puzzles from self-play, a file of positions or a GCG game, or solves the ones in a puzzle file
+fn puzzles() -> Result<(), UserCancelError> {
+    let input = get_user_input_string_uppercase(
+        "1) Solve puzzles\n2) Make puzzles by self-play\n3) Make puzzles from a file of positions or a GCG game",
+        "1",
+        true,
+    )?;
+    let file = get_user_input_string("Puzzle file", DEFAULT_PUZZLES_FILE, true)?;
+    if input == "1" {
+        return match load_puzzles(Path::new(&file)) {
+            Ok(puzzles) => solve_puzzles(&puzzles),
+            Err(e) => {
+                println!("Could not read {}: {}", file, e);
+                get_user_input_string_uppercase("Press enter to continue...", "", false)?;
+                Ok(())
+            }
+        };
+    }
+
+    let kinds: Vec<PuzzleKind> = loop {
+        let input = get_user_input_string(
+            "Which kinds of puzzle? (any of 1 best score, 2 best equity, 3 bingo, 4 out-play)",
+            "1234",
+            true,
+        )?;
+        let kinds: Vec<PuzzleKind> = input
+            .chars()
+            .filter_map(|c| c.to_digit(10))
+            .filter_map(|d| PuzzleKind::ALL.get((d as usize).wrapping_sub(1)).copied())
+            .unique()
+            .collect();
+        if !kinds.is_empty() {
+            break kinds;
+        }
+        println!("Please enter some of 1, 2, 3 and 4");
+    };
+    let scrabble_variant = get_user_input_scrabble_variant()?;
+    let puzzles = match input.as_str() {
+        "2" => {
+            let count = get_user_input_integer("How many puzzles?", "20", 1, 10_000, true)?;
+            let seed = get_user_input_integer("Seed for the tiles drawn", "1", 1, i32::MAX, true)?;
+            println!("Playing...");
+            self_play_puzzles(count as usize, &kinds, seed as u64, scrabble_variant)
+        }
+        _ => {
+            let word_check = get_user_input_word_check()?;
+            let positions = get_user_input_string(
+                "File of positions, one a line, or a .gcg file",
+                "positions.txt",
+                true,
+            )?;
+            let contents = match fs::read_to_string(&positions) {
+                Ok(contents) => contents,
+                Err(e) => {
+                    println!("Could not read {}: {}", positions, e);
+                    return Ok(());
+                }
+            };
+            let mut puzzles = vec![];
+            if positions.to_lowercase().ends_with(".gcg") {
+                match read_gcg(&contents, scrabble_variant, word_check)
+                    .and_then(|game| puzzles_from_game(&game, &kinds))
+                {
+                    Ok(game_puzzles) => puzzles = game_puzzles,
+                    Err(e) => println!("{}: {}", positions, e),
+                }
+            } else {
+                for line in contents.lines().filter(|line| !line.trim().is_empty()) {
+                    match read_position(line, scrabble_variant, word_check) {
+                        Ok(game) => puzzles.extend(puzzles_from_position(&game, &kinds)),
+                        Err(e) => println!("{}: {}", e, line),
+                    }
+                }
+            }
+            puzzles
+        }
+    };
+    match save_puzzles(Path::new(&file), &puzzles) {
+        Ok(()) => println!("Saved {} puzzles to {}", puzzles.len(), file),
+        Err(e) => println!("Could not save to {}: {}", file, e),
+    }
+    get_user_input_string_uppercase("Press enter to continue...", "", false)?;
+    Ok(())
+}
+
+fn solve_puzzles(puzzles: &[Puzzle]) -> Result<(), UserCancelError> {
+    let mut solved = 0;
+    for (i, puzzle) in puzzles.iter().enumerate() {
+        let game = match puzzle.game() {
+            Ok(game) => game,
+            Err(e) => {
+                println!("Puzzle {}: {}", i + 1, e);
+                continue;
+            }
+        };
+        println!("{}", game);
+        println!("Puzzle {} of {}: {}", i + 1, puzzles.len(), puzzle);
+        loop {
+            let position = get_user_input_position("Enter starting position", "H8")?;
+            let direction = get_user_input_direction("Enter direction")?;
+            let tiles =
+                get_user_input_tile_list("Enter tiles to play, lower case for a blank", false)?;
+            match puzzle.check_answer(&game, position, direction, &tiles) {
+                Ok(true) => {
+                    println!("Correct!");
+                    solved += 1;
+                }
+                Ok(false) => println!("Not quite."),
+                Err(e) => {
+                    println!("That isn't a legal play: {:?}", e);
+                    continue;
+                }
+            }
+            break;
+        }
+        println!("Solutions:");
+        for solution in puzzle.solutions.iter() {
+            println!("  {}", solution);
+        }
+        get_user_input_string_uppercase("Press enter to continue...", "", false)?;
+    }
+    println!("You solved {} of {} puzzles", solved, puzzles.len());
+    get_user_input_string_uppercase("Press enter to continue...", "", false)?;
+    Ok(())
+}
+
 fn human_vs_computer() -> Result<Game, UserCancelError> {
     let scrabble_variant = get_user_input_scrabble_variant()?;
-    let human_name = get_user_input_string("What is your name?", "Human", true)?;
+    let word_check = get_user_input_word_check()?;
+    let human_name = get_user_input_profile("What is your name?", "Human")?;
     let human_first = get_user_input_bool(
         &format!("Do you want to go first {}?", &human_name),
         "Y",
         true,
     )?;
+    let bot = get_user_input_bot_config()?;
     let number_of_players = 2;
     let human_player = Player::new(PlayerType::Human);
-    let computer_player = Player::new(PlayerType::Computer);
+    let computer_player = Player::new_computer(bot);
+    let computer_name = format!("Computer ({})", bot);
     let player_name = if human_first {
-        vec![human_name, "Computer".to_string()]
+        vec![human_name, computer_name]
     } else {
-        vec!["Computer".to_string(), human_name]
+        vec![computer_name, human_name]
     };
 
     let players = if human_first {
         [
             human_player,
-            computer_player,
-            computer_player,
+            computer_player.clone(),
+            computer_player.clone(),
             computer_player,
         ]
     } else {
         [
-            computer_player,
+            computer_player.clone(),
             human_player,
-            computer_player,
+            computer_player.clone(),
             computer_player,
         ]
     };
 
-    let mut game = Game::new(&scrabble_variant, number_of_players, players, player_name);
+    let mut game = Game::new_with_word_check(
+        scrabble_variant,
+        number_of_players,
+        players,
+        player_name,
+        word_check,
+    );
 
     play_game(&mut game)?;
     Ok(game)
@@ -205,6 +778,7 @@ fn human_move(game: &mut Game) -> Result<(), UserCancelError> {
     println!("3) Exchange tiles");
     println!("4) Show cell info");
     println!("5) Computer suggestion");
+    println!("6) Show position");
     println!("9) Resign Game");
     println!("0) Quit Program");
 
@@ -218,7 +792,9 @@ fn human_move(game: &mut Game) -> Result<(), UserCancelError> {
             game.pass();
         }
         "3" => {
-            if let Ok(tile_list) = get_user_input_tile_list("Enter tiles to exchange") {
+            if let Ok(tile_list) =
+                get_user_input_tile_list("Enter tiles to exchange, ? for a blank", true)
+            {
                 if let Err(e) = game.exchange_tiles(&tile_list) {
                     println!("Error: {:?}", e);
                 }
@@ -236,6 +812,10 @@ fn human_move(game: &mut Game) -> Result<(), UserCancelError> {
         "5" => {
             computer_suggestion(game).unwrap();
         }
+        "6" => {
+            println!("{}", write_position(game));
+            let _ = get_user_input_string_uppercase("Press enter to continue...", "", false);
+        }
         "0" => {
             if get_user_input_bool(
                 "Are you sure you want to quit the program? (Y or N)",
@@ -290,22 +870,36 @@ fn computer_suggestion(game: &mut Game) -> Result<(), UserCancelError> {
     Ok(())
 }
 
+// a play written the usual way, with letters already on the board in brackets
 fn play_word(game: &mut Game) -> Result<(), UserCancelError> {
-    // capture the starting cell
-
     loop {
-        let starting_position = get_user_input_position("Enter starting position", "H8")?;
-        let direction = get_user_input_direction("Which direction? ('H' or 'V')")?;
-        let tiles = get_user_input_tile_list("Enter tiles to play:  ")?;
-
-        match game.human_move(starting_position, direction, &tiles) {
+        let play = get_user_input_string(
+            "Enter your play, e.g. 8D QU(I)Z across from D8, or D8 QU(I)Z down, lower case for a blank",
+            "",
+            false,
+        )?;
+        let placements = match read_play_placements(&game.board, &play) {
+            Ok(placements) => placements,
+            Err(e) => {
+                println!("Move rejected: {}", e);
+                continue;
+            }
+        };
+        let result = placed_move(game, &placements)
+            .and_then(|game_move| game.apply_action(&Action::Play(game_move)));
+        match result {
             Ok(_) => {
                 println!("{}", game);
                 break;
             }
-            Err(e) => {
-                println!("Move rejected: {:?}", e);
+            Err(MoveError::InvalidWord(word)) => {
+                println!(
+                    "Move rejected: {} isn't a word.  {}",
+                    word,
+                    describe_word(&word)
+                )
             }
+            Err(e) => println!("Move rejected: {}", e),
         }
     }
 
@@ -316,7 +910,7 @@ fn human_vs_human() -> Result<Game, UserCancelError> {
     let mut game = Game::new(
         &SCRABBLE_VARIANT_OFFICIAL,
         2,
-        [Player::new(PlayerType::Human); 4],
+        std::array::from_fn(|_| Player::new(PlayerType::Human)),
         vec![
             "Player 1".to_string(),
             "Player 2".to_string(),
@@ -334,10 +928,173 @@ fn look_up_word() {
     if let Ok(word) = get_user_input_string_uppercase("Enter a word:  ", "0", true) {
         let result = is_word(&word);
         match result {
-        true => println!("{word} is a word.  Follow the link for the definition:  https://www.collinsdictionary.com/dictionary/english/{word}"),
-        false => println!("{} is not a word", word),
+            true => println!("{} is a word.  {}", tag_word(&word), describe_word(&word)),
+            false => match comparison_lexicon() {
+                Some(other) if other.contains(&word) => println!(
+                    "{} is not a word, but is valid in {}",
+                    tag_word(&word),
+                    other.name
+                ),
+                _ => println!("{} is not a word", word),
+            },
+        }
     }
+}
+
+fn compare_word_lists() -> Result<(), UserCancelError> {
+    let other = match comparison_lexicon() {
+        Some(other) => other,
+        None => loop {
+            let file = get_user_input_string(
+                "Enter the word list file to compare with",
+                DEFAULT_COMPARISON_LEXICON_FILE,
+                true,
+            )?;
+            match load_comparison_lexicon(Path::new(&file)) {
+                Ok(other) => break other,
+                Err(e) => println!("Could not read {}: {}", file, e),
+            }
+        },
+    };
+    let diff = compare_lexicons(&Lexicon::builtin(), other);
+    println!("{}", diff);
+    let max_length =
+        get_user_input_integer("List differences up to what length?", "3", 2, 15, true)? as usize;
+    for (name, words) in [
+        (&diff.primary, &diff.primary_only),
+        (&diff.other, &diff.other_only),
+    ] {
+        println!("\nOnly in {}:", name);
+        println!(
+            "{}",
+            words
+                .iter()
+                .filter(|w| w.len() <= max_length)
+                .map(|w| tag_word(w))
+                .join(" ")
+        );
     }
+    get_user_input_string_uppercase("Press enter to continue...", "", false)?;
+    Ok(())
+}
+
+fn find_words() -> Result<(), UserCancelError> {
+    let scrabble_variant = get_user_input_scrabble_variant()?;
+    loop {
+        let input = get_user_input_string_uppercase(
+            "Enter a search, e.g.  ?A?E   *ING   [AEIOU]Z*   anagram=AEIRST?   contains=QU   length=5-8",
+            "0",
+            false,
+        )?;
+        let result = WordQuery::from_str(&input)
+            .and_then(|query| word_finder::find_words(&query, scrabble_variant));
+        match result {
+            Ok(matches) => {
+                for word_match in matches.iter().take(100) {
+                    println!("{}", word_match);
+                }
+                if matches.len() > 100 {
+                    println!("... and {} more", matches.len() - 100);
+                }
+                println!("{} words found", matches.len());
+            }
+            Err(e) => println!("{}", e),
+        }
+    }
+}
+
+fn word_study() -> Result<(), UserCancelError> {
+    let kind = loop {
+        let input = get_user_input_string_uppercase(
+            "What would you like to study?\n1) Anagrams\n2) Bingos from a 7 letter rack\n3) Bingos through a letter on the board\n4) Front and back hooks",
+            "1",
+            true,
+        )?;
+        match input.as_str() {
+            "1" => break QuizKind::Anagram,
+            "2" => break QuizKind::Bingo7,
+            "3" => break QuizKind::Bingo8,
+            "4" => break QuizKind::Hooks,
+            _ => println!("Please enter '1' to '4'"),
+        }
+    };
+    let length = match kind {
+        QuizKind::Anagram | QuizKind::Hooks => {
+            get_user_input_integer("Word length", "4", 2, 15, true)? as usize
+        }
+        QuizKind::Bingo7 | QuizKind::Bingo8 => 7,
+    };
+    let band_start = get_user_input_integer(
+        "Start of probability band (1 is the most likely)",
+        "1",
+        1,
+        100000,
+        true,
+    )? as usize;
+    let band_end = get_user_input_integer(
+        "End of probability band",
+        &(band_start + 499).to_string(),
+        band_start as i32,
+        100000,
+        true,
+    )? as usize;
+    let questions = get_user_input_integer("Number of questions", "10", 1, 100, true)? as usize;
+    let seconds = get_user_input_integer(
+        "Seconds allowed per question (-1 for no limit)",
+        "30",
+        -1,
+        600,
+        true,
+    )?;
+    let spec = QuizSpec {
+        kind,
+        length,
+        band: Some((band_start, band_end)),
+        questions,
+        time_limit: if seconds < 0 {
+            None
+        } else {
+            Some(Duration::from_secs(seconds as u64))
+        },
+    };
+
+    let mut progress = match Progress::load(Path::new(DEFAULT_PROGRESS_FILE)) {
+        Ok(progress) => progress,
+        Err(e) => {
+            println!("Could not read {}: {}", DEFAULT_PROGRESS_FILE, e);
+            return Ok(());
+        }
+    };
+    let today = training::today();
+    let quiz = build_quiz(&spec, &SCRABBLE_VARIANT_OFFICIAL, &progress, today);
+    if quiz.is_empty() {
+        println!("Nothing is due for review in that band.");
+    }
+
+    let mut summary = QuizSummary::default();
+    for (i, question) in quiz.iter().enumerate() {
+        print!("\x1B[2J\x1B[1;1H");
+        println!("{} - question {} of {}", kind, i + 1, quiz.len());
+        let timer = Timer::new(true);
+        let response = get_user_input_string_uppercase(&question.to_string(), "", false);
+        let elapsed = timer.elapsed();
+        let response = match response {
+            Ok(response) => response,
+            Err(_) => break,
+        };
+        let outcome = question.mark(&response, elapsed, spec.time_limit);
+        progress.record(&question.key(), outcome.is_correct(), today);
+        summary.add(&outcome);
+        println!("{}", outcome);
+        get_user_input_string_uppercase("Press enter to continue...", "", false)?;
+    }
+
+    if let Err(e) = progress.save() {
+        println!("Could not save progress: {}", e);
+    }
+    println!("{}", summary);
+    get_user_input_string_uppercase("Press enter to continue...", "", false)?;
+    Ok(())
 }
 
 fn get_user_input_string(
@@ -363,13 +1120,90 @@ fn get_user_input_string(
     }
 }
 
-fn get_user_player_name(player_number: usize) -> Result<String, UserCancelError> {
-    let default = format!("Player {}", player_number);
-    get_user_input_string(
-        &format!("Enter name for player {}", player_number),
-        &default,
-        true,
-    )
+/*
+Picks a player profile by number or name, so their rating is updated after the game.  A new name
+can be saved as a profile, or played under without one as a guest.
+*/
+fn get_user_input_profile(caption: &str, default: &str) -> Result<String, UserCancelError> {
+    let mut profiles = match Profiles::load(Path::new(DEFAULT_PROFILES_FILE)) {
+        Ok(profiles) => profiles,
+        Err(e) => {
+            println!("Could not read {}: {}", DEFAULT_PROFILES_FILE, e);
+            return get_user_input_string(caption, default, true);
+        }
+    };
+    let mut prompt = caption.to_string();
+    for (i, profile) in profiles.profiles.iter().enumerate() {
+        prompt.push_str(&format!(
+            "\n{}) {} ({:.0})",
+            i + 1,
+            profile.name,
+            profile.rating
+        ));
+    }
+    let input = get_user_input_string(&prompt, default, true)?;
+    if let Some(profile) = input
+        .parse::<usize>()
+        .ok()
+        .and_then(|n| profiles.profiles.get(n.wrapping_sub(1)))
+    {
+        return Ok(profile.name.clone());
+    }
+    if profiles.get(&input).is_none()
+        && input != default
+        && get_user_input_bool(&format!("Save a profile for {}?", input), "Y", true)?
+    {
+        if let Err(e) = profiles.add(&input) {
+            println!("{}", e);
+        } else if let Err(e) = profiles.save() {
+            println!("Could not save {}: {}", DEFAULT_PROFILES_FILE, e);
+        } else {
+            println!("Saved a profile for {}", input);
+        }
+    }
+    Ok(input)
+}
+
+// updates the ratings of the players with profiles
+fn record_ratings(game: &Game) {
+    let mut profiles = match Profiles::load(Path::new(DEFAULT_PROFILES_FILE)) {
+        Ok(profiles) => profiles,
+        Err(e) => {
+            println!("Could not read {}: {}", DEFAULT_PROFILES_FILE, e);
+            return;
+        }
+    };
+    let date = chrono::Local::now().format("%Y-%m-%d").to_string();
+    let changes = profiles.record_game(&game_statistics(game), &date);
+    if changes.is_empty() {
+        return;
+    }
+    if let Err(e) = profiles.save() {
+        println!("Could not save {}: {}", DEFAULT_PROFILES_FILE, e);
+        return;
+    }
+    for (name, change) in changes {
+        println!(
+            "{} is now rated {:.0} ({:+.0})",
+            name,
+            profiles.get(&name).unwrap().rating,
+            change
+        );
+    }
+}
+
+fn show_ratings() -> Result<(), UserCancelError> {
+    match Profiles::load(Path::new(DEFAULT_PROFILES_FILE)) {
+        Ok(profiles) if profiles.profiles.is_empty() => println!("No player profiles yet"),
+        Ok(profiles) => {
+            for (i, profile) in profiles.ranked().iter().enumerate() {
+                println!("{:3} {}", i + 1, profile);
+            }
+        }
+        Err(e) => println!("Could not read {}: {}", DEFAULT_PROFILES_FILE, e),
+    }
+    get_user_input_string_uppercase("Press enter to continue...", "", false)?;
+    Ok(())
 }
 
 fn get_user_input_string_uppercase(
@@ -405,12 +1239,16 @@ fn get_user_input_position(caption: &str, default: &str) -> Result<Position, Use
     }
 }
 
-fn get_user_input_tile_list(caption: &str) -> Result<TileList, UserCancelError> {
+// lower case is a blank given that letter, unless it's all read as upper case
+fn get_user_input_tile_list(caption: &str, uppercase: bool) -> Result<TileList, UserCancelError> {
     loop {
-        let input = get_user_input_string_uppercase(caption, "0", false)?;
+        let input = match uppercase {
+            true => get_user_input_string_uppercase(caption, "0", false)?,
+            false => get_user_input_string(caption, "0", false)?,
+        };
         match TileList::try_from(input.as_str()) {
             Ok(t) => return Ok(t),
-            Err(e) => println!("{:?}", e),
+            Err(e) => println!("{}", e),
         }
     }
 }
@@ -477,7 +1315,22 @@ fn get_user_input_scrabble_variant() -> Result<&'static ScrabbleVariant, UserCan
     }
 }
 
-fn get_user_input_player_type() -> Result<PlayerType, UserCancelError> {
+fn get_user_input_word_check() -> Result<WordCheck, UserCancelError> {
+    loop {
+        let input = get_user_input_string_uppercase(
+            "Which word rules should we use?\n1) Standard\n2) Clabbers (any anagram of a word is allowed)",
+            "1",
+            true,
+        )?;
+        match input.as_str() {
+            "1" => return Ok(WordCheck::Exact),
+            "2" => return Ok(WordCheck::Clabbers),
+            _ => println!("Please enter '1' or '2'"),
+        }
+    }
+}
+
+fn get_user_input_player() -> Result<Player, UserCancelError> {
     loop {
         let input = get_user_input_string_uppercase(
             "Which player type should we use?\n1) Human\n2) Computer",
@@ -485,13 +1338,92 @@ fn get_user_input_player_type() -> Result<PlayerType, UserCancelError> {
             true,
         )?;
         match input.as_str() {
-            "1" => return Ok(PlayerType::Human),
-            "2" => return Ok(PlayerType::Computer),
+            "1" => return Ok(Player::new(PlayerType::Human)),
+            "2" => return Ok(Player::new_computer(get_user_input_bot_config()?)),
             _ => println!("Please enter '1' or '2'"),
         }
     }
 }
 
+fn get_user_input_bot_config() -> Result<BotConfig, UserCancelError> {
+    let level = loop {
+        let input = get_user_input_string_uppercase(
+            "How strong should the computer be?\n1) Expert\n2) Intermediate\n3) Beginner\n4) Custom",
+            "1",
+            true,
+        )?;
+        match input.as_str() {
+            "1" => break BotLevel::Expert,
+            "2" => break BotLevel::Intermediate,
+            "3" => break BotLevel::Beginner,
+            "4" => break BotLevel::Custom,
+            _ => println!("Please enter a number between 1 and 4"),
+        }
+    };
+    let persona = loop {
+        let input = get_user_input_string_uppercase(
+            "How should the computer play?\n1) Standard (highest score)\n2) Aggressive (opens the board)\n3) Defensive (keeps the board closed)\n4) Bingo hunter (keeps good tiles)",
+            "1",
+            true,
+        )?;
+        match input.as_str() {
+            "1" => break Persona::Standard,
+            "2" => break Persona::Aggressive,
+            "3" => break Persona::Defensive,
+            "4" => break Persona::BingoHunter,
+            _ => println!("Please enter a number between 1 and 4"),
+        }
+    };
+    let mut bot = BotConfig::new(level, persona);
+    // how many words the computer knows is counted down the frequency list, without one it knows them all
+    if !has_frequency_list() && (bot.vocabulary.is_some() || level == BotLevel::Custom) {
+        println!(
+            "There is no word frequency list in {} (or SCRABBLE_FREQUENCY_LIST), so the computer will know every word",
+            DEFAULT_FREQUENCY_FILE
+        );
+    }
+    if level == BotLevel::Custom {
+        if has_frequency_list() {
+            let vocabulary = get_user_input_integer(
+                "How many of the most common words does the computer know? (0 for all of them)",
+                "0",
+                0,
+                1_000_000,
+                true,
+            )?;
+            bot.vocabulary = (vocabulary > 0).then_some(vocabulary as usize);
+        }
+        let rank = get_user_input_integer(
+            "Which move should it choose? (1 for the best, 2 for the second best, ...)",
+            "1",
+            1,
+            100,
+            true,
+        )?;
+        bot.choice = match rank {
+            1 => MoveChoice::Best,
+            k => MoveChoice::Rank(k as usize),
+        };
+        let seconds = get_user_input_integer(
+            "How many seconds can it think for? (0 for no limit)",
+            "0",
+            0,
+            3600,
+            true,
+        )?;
+        bot.time_limit = (seconds > 0).then(|| Duration::from_secs(seconds as u64));
+        let minutes = get_user_input_integer(
+            "How many minutes can it think for over the whole game? (0 for no limit)",
+            "0",
+            0,
+            600,
+            true,
+        )?;
+        bot.clock = (minutes > 0).then(|| Duration::from_secs(minutes as u64 * 60));
+    }
+    Ok(bot)
+}
+
 /*
 fn test_anagram_version() {
     let timer = Timer::new("show_totals()");
@@ -0,0 +1,490 @@
+use crate::board::{Board, CellValue, ScrabbleVariant};
+use crate::pos::Position;
+use crate::strategy::GameView;
+use crate::tiles::{Letter, Tile, TileBag, TileList};
+use crate::word_list::WordCheck;
+use crate::{Direction, Game, Player, PlayerType, TScore};
+
+/*
+A position written on one line, in the style of chess FEN.  The board comes first, a row at a time
+from the top separated by '/', with a number for a run of empty squares and blanks in lower case.
+Then the racks and the scores of each player separated by '/', the tiles in the bag, and the
+number of the player to move.  Blanks on a rack or in the bag are '?' and an empty one is '-'.
+A rack can be just the number of tiles on it, when they aren't known, and they are then made up
+from the tiles left over.  The bag can be left out, it is then whatever tiles aren't on the board
+or a rack.
+
+  15/15/15/15/15/15/15/7CAt5/15/15/15/15/15/15/15 AEINRST/DEGOORU 10/0 2
+  15/15/15/15/15/15/15/7CAt5/15/15/15/15/15/15/15 AEINRST/7 10/0 2
+*/
+
+pub fn read_tiles(s: &str) -> Result<TileBag, String> {
+    let mut bag = TileBag::new_empty();
+    if s == "-" {
+        return Ok(bag);
+    }
+    // the total is kept to what a bag can count, so no one letter can go over either
+    let mut total = 0u8;
+    for c in s.chars() {
+        total = total
+            .checked_add(1)
+            .ok_or(format!("There are too many tiles in {}", s))?;
+        match c {
+            '*' | '?' => bag.blanks += 1,
+            'A'..='Z' => bag.letters[Letter::from(c).as_usize()] += 1,
+            _ => return Err(format!("Invalid tile '{}' in {}", c, s)),
+        }
+    }
+    Ok(bag)
+}
+
+// a rack, or the number of tiles on it when they aren't shown
+fn read_rack(s: &str) -> Result<(TileBag, u8), String> {
+    match s.parse::<u8>() {
+        Ok(count) => Ok((TileBag::new_empty(), count)),
+        Err(_) => Ok((read_tiles(s)?, 0)),
+    }
+}
+
+fn write_tiles(bag: &TileBag) -> String {
+    match bag.is_empty() {
+        true => "-".to_string(),
+        false => bag.to_string(),
+    }
+}
+
+pub fn write_board(board: &Board) -> String {
+    let variant = board.scrabble_variant;
+    let mut rows = vec![];
+    for y in 0..variant.height {
+        let mut row = String::new();
+        let mut empty = 0;
+        for x in 0..variant.width {
+            match board.get_cell(x, y).value {
+                CellValue::Empty { .. } => empty += 1,
+                CellValue::Filled {
+                    letter, is_blank, ..
+                } => {
+                    if empty > 0 {
+                        row.push_str(&empty.to_string());
+                        empty = 0;
+                    }
+                    match is_blank {
+                        true => row.push(letter.as_char().to_ascii_lowercase()),
+                        false => row.push(letter.as_char()),
+                    }
+                }
+            }
+        }
+        if empty > 0 {
+            row.push_str(&empty.to_string());
+        }
+        rows.push(row);
+    }
+    rows.join("/")
+}
+
+// the letters are placed first, then the letters allowed in each empty square are worked out
+pub fn read_board(
+    s: &str,
+    variant: &'static ScrabbleVariant,
+    word_check: WordCheck,
+) -> Result<Board, String> {
+    let mut board = Board::new(variant, word_check);
+    let rows: Vec<&str> = s.split('/').collect();
+    if rows.len() != variant.height as usize {
+        return Err(format!(
+            "The board needs {} rows, not {}",
+            variant.height,
+            rows.len()
+        ));
+    }
+    for (y, row) in rows.iter().enumerate() {
+        let mut x = 0usize;
+        let mut chars = row.chars().peekable();
+        while let Some(c) = chars.next() {
+            if let Some(digit) = c.to_digit(10) {
+                let mut empty = digit as usize;
+                while let Some(digit) = chars.peek().and_then(|c| c.to_digit(10)) {
+                    empty = empty * 10 + digit as usize;
+                    // a long run of digits stops as soon as it's too wide
+                    if x + empty > variant.width as usize {
+                        return Err(format!("Row {} is too wide", y + 1));
+                    }
+                    chars.next();
+                }
+                x += empty;
+                continue;
+            }
+            if x >= variant.width as usize {
+                return Err(format!("Row {} is too wide", y + 1));
+            }
+            let tile = match c {
+                'A'..='Z' => Tile::Letter(Letter::from(c)),
+                'a'..='z' => Tile::Blank {
+                    acting_as_letter: Some(Letter::from(c.to_ascii_uppercase())),
+                },
+                _ => return Err(format!("Invalid square '{}' in row {}", c, y + 1)),
+            };
+            board.get_cell_mut(x as u8, y as u8).set_tile(tile);
+            x += 1;
+        }
+        if x != variant.width as usize {
+            return Err(format!(
+                "Row {} is {} squares wide, not {}",
+                y + 1,
+                x,
+                variant.width
+            ));
+        }
+    }
+    board.reset_last_move_flags();
+    for y in 0..variant.height {
+        for x in 0..variant.width {
+            if !board.get_cell(x, y).is_empty() {
+                board.update_word_gaps(Position::new(x, y), Direction::Horizontal);
+                board.update_word_gaps(Position::new(x, y), Direction::Vertical);
+            }
+        }
+    }
+    check_board(&board)?;
+    Ok(board)
+}
+
+/*
+A board that could have come from a game: every run of two or more letters across or down is a
+word, and the tiles all join up with one on the centre square.
+*/
+fn check_board(board: &Board) -> Result<(), String> {
+    let variant = board.scrabble_variant;
+    let letter_at = |x: u8, y: u8| match board.get_cell(x, y).value {
+        CellValue::Filled { letter, .. } => Some(letter.as_char()),
+        CellValue::Empty { .. } => None,
+    };
+    let mut runs: Vec<Vec<Option<char>>> = vec![];
+    for y in 0..variant.height {
+        runs.push((0..variant.width).map(|x| letter_at(x, y)).collect());
+    }
+    for x in 0..variant.width {
+        runs.push((0..variant.height).map(|y| letter_at(x, y)).collect());
+    }
+    for run in runs {
+        for word in run.split(|square| square.is_none()) {
+            let word: String = word.iter().flatten().collect();
+            if word.len() > 1 && !board.word_check.is_valid(&word) {
+                return Err(format!("{} isn't a word", word));
+            }
+        }
+    }
+
+    let filled = board.cells.iter().filter(|cell| !cell.is_empty()).count();
+    if filled == 0 {
+        return Ok(());
+    }
+    let centre = Position::new(7, 7);
+    if board.get_cell_pos(centre).is_empty() {
+        return Err("There must be a tile on the centre square".to_string());
+    }
+    // spread out from the centre over the filled squares
+    let mut joined = vec![centre];
+    let mut next = 0;
+    while next < joined.len() {
+        let pos = joined[next];
+        next += 1;
+        for direction in [Direction::Horizontal, Direction::Vertical] {
+            for neighbour in [
+                pos.try_step_forward(direction),
+                pos.try_step_backward(direction),
+            ]
+            .into_iter()
+            .flatten()
+            {
+                if !board.get_cell_pos(neighbour).is_empty() && !joined.contains(&neighbour) {
+                    joined.push(neighbour);
+                }
+            }
+        }
+    }
+    if joined.len() != filled {
+        return Err("Some of the tiles aren't joined to the rest".to_string());
+    }
+    Ok(())
+}
+
+// the tiles played on the board, counting blanks as blanks
+pub(crate) fn board_tiles(board: &Board) -> TileBag {
+    let mut tiles = TileBag::new_empty();
+    for cell in board.cells.iter() {
+        match cell.value {
+            CellValue::Filled { is_blank: true, .. } => tiles.blanks += 1,
+            CellValue::Filled { letter, .. } => tiles.letters[letter.as_usize()] += 1,
+            CellValue::Empty { .. } => {}
+        }
+    }
+    tiles
+}
+
+pub fn write_position(game: &Game) -> String {
+    let players = 0..game.number_of_players;
+    format!(
+        "{} {} {} {} {}",
+        write_board(&game.board),
+        players
+            .clone()
+            .map(|i| write_tiles(&game.player[i].rack))
+            .collect::<Vec<_>>()
+            .join("/"),
+        players
+            .map(|i| game.player[i].score.to_string())
+            .collect::<Vec<_>>()
+            .join("/"),
+        write_tiles(&game.bag),
+        game.current_player + 1
+    )
+}
+
+/*
+The position as the player to move sees it.  The other racks are only the number of tiles on them
+and the bag is left out, so those tiles are made up from the ones the player can't see.
+*/
+pub fn write_view_position(view: &GameView) -> String {
+    let rack_counts = view.rack_counts();
+    format!(
+        "{} {} {} {}",
+        write_board(view.board()),
+        (0..view.number_of_players())
+            .map(|i| match i == view.player() {
+                true => write_tiles(&view.rack()),
+                false => rack_counts[i].to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join("/"),
+        view.scores()
+            .iter()
+            .map(|score| score.to_string())
+            .collect::<Vec<_>>()
+            .join("/"),
+        view.player() + 1
+    )
+}
+
+// adds tiles to the ones used so far, which mustn't come to more of any tile than the variant has
+fn use_tiles(used: &mut TileBag, tiles: &TileBag, all: &TileBag) -> Result<(), String> {
+    if used.blanks as usize + tiles.blanks as usize > all.blanks as usize {
+        return Err(format!("There are more than {} blanks", all.blanks));
+    }
+    used.blanks += tiles.blanks;
+    for (i, available) in all.letters.iter().enumerate() {
+        if used.letters[i] as usize + tiles.letters[i] as usize > *available as usize {
+            return Err(format!(
+                "There are more than {} {}",
+                available,
+                Letter(i as u8)
+            ));
+        }
+        used.letters[i] += tiles.letters[i];
+    }
+    Ok(())
+}
+
+/*
+Sets up a game from a position, with human players.  The position must not use more of any tile
+than the variant has.
+*/
+pub fn read_position(
+    s: &str,
+    variant: &'static ScrabbleVariant,
+    word_check: WordCheck,
+) -> Result<Game, String> {
+    let fields: Vec<&str> = s.split_whitespace().collect();
+    let (board, racks, scores, bag, turn) = match fields[..] {
+        [board, racks, scores, bag, turn] => (board, racks, scores, Some(bag), turn),
+        [board, racks, scores, turn] => (board, racks, scores, None, turn),
+        _ => {
+            return Err(
+                "A position needs the board, racks, scores, bag (optional) and player to move"
+                    .to_string(),
+            )
+        }
+    };
+    let board = read_board(board, variant, word_check)?;
+    let mut racks = racks
+        .split('/')
+        .map(read_rack)
+        .collect::<Result<Vec<_>, _>>()?;
+    if !(2..=4).contains(&racks.len()) {
+        return Err("There must be between 2 and 4 players".to_string());
+    }
+    if racks.iter().any(|(rack, hidden)| rack.count() + hidden > 7) {
+        return Err("A rack can't have more than 7 tiles".to_string());
+    }
+    let scores = scores
+        .split('/')
+        .map(|score| score.parse::<TScore>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Invalid score: {}", e))?;
+    if scores.len() != racks.len() {
+        return Err("There must be a score for each rack".to_string());
+    }
+    let turn = match turn.parse::<usize>() {
+        Ok(turn) if (1..=racks.len()).contains(&turn) => turn - 1,
+        _ => return Err(format!("Invalid player to move: {}", turn)),
+    };
+
+    let all = TileBag::new(variant);
+    let mut used = TileBag::new_empty();
+    use_tiles(&mut used, &board_tiles(&board), &all)?;
+    for (rack, _) in racks.iter() {
+        use_tiles(&mut used, rack, &all)?;
+    }
+    let bag = match bag {
+        Some(bag) => {
+            let bag = read_tiles(bag)?;
+            use_tiles(&mut used, &bag, &all)?;
+            Some(bag)
+        }
+        None => None,
+    };
+
+    // the racks which aren't shown are dealt what's left, then the bag is the rest
+    let mut left = all;
+    left.blanks = left.blanks.saturating_sub(used.blanks);
+    for (count, used) in left.letters.iter_mut().zip(used.letters.iter()) {
+        *count = count.saturating_sub(*used);
+    }
+    let mut left = TileList::from(left).0;
+    for (rack, hidden) in racks.iter_mut() {
+        if *hidden as usize > left.len() {
+            return Err("There aren't enough tiles left for the racks".to_string());
+        }
+        rack.add_tile_list(&TileList(left.drain(..*hidden as usize).collect()));
+    }
+    let bag = bag.unwrap_or_else(|| TileList(left).into());
+
+    let number_of_players = racks.len();
+    let mut game = Game::new_with_word_check(
+        variant,
+        number_of_players,
+        std::array::from_fn(|_| Player::new(PlayerType::Human)),
+        (0..number_of_players)
+            .map(|i| format!("Player {}", i + 1))
+            .collect(),
+        word_check,
+    );
+    game.first_move = board.cells.iter().all(|cell| cell.is_empty());
+    game.board = board;
+    game.bag = bag;
+    for (i, ((rack, _), score)) in racks.into_iter().zip(scores).enumerate() {
+        game.player[i].rack = rack;
+        game.player[i].score = score;
+    }
+    game.current_player_mut().timer.stop();
+    game.current_player = turn;
+    game.current_player_mut().timer.start();
+    Ok(game)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::SCRABBLE_VARIANT_OFFICIAL;
+    use crate::bot::BotConfig;
+    use crate::tests::two_player_game;
+    use crate::tiles::TileList;
+    use std::time::Duration;
+
+    #[test]
+    fn test_position() {
+        let mut game = two_player_game(WordCheck::Exact, "CASEIR?");
+        game.human_move(
+            Position::new(7, 7),
+            Direction::Horizontal,
+            &TileList::try_from("CA*T").unwrap(),
+        )
+        .unwrap();
+
+        let position = write_position(&game);
+        assert!(position.starts_with("15/15/15/15/15/15/15/7CAt5/15/"));
+        let read = read_position(&position, &SCRABBLE_VARIANT_OFFICIAL, WordCheck::Exact).unwrap();
+        assert_eq!(write_position(&read), position);
+        let mut board = game.board;
+        board.reset_last_move_flags();
+        assert_eq!(read.board, board);
+        assert_eq!(read.current_player, 1);
+        assert!(!read.first_move);
+
+        // without the bag it's the tiles which are left
+        let fields: Vec<&str> = position.split(' ').collect();
+        let without_bag = [fields[0], fields[1], fields[2], fields[4]].join(" ");
+        let read =
+            read_position(&without_bag, &SCRABBLE_VARIANT_OFFICIAL, WordCheck::Exact).unwrap();
+        assert_eq!(read.bag, game.bag);
+
+        assert!(read_position(
+            "15/15 - 0/0 1",
+            &SCRABBLE_VARIANT_OFFICIAL,
+            WordCheck::Exact
+        )
+        .is_err());
+
+        // too many tiles, or squares, is an error rather than counting past what fits
+        assert!(read_tiles(&"A".repeat(300)).is_err());
+        assert!(read_tiles(&"AB".repeat(150)).is_err());
+        let empty = "15/".repeat(14);
+        for position in [
+            format!("{}15 {}/- 0/0 1", empty, "A".repeat(300)),
+            format!("{}9999999999999999999999 -/- 0/0 1", empty),
+            format!("{}15 -/- 0/0 {} 1", empty, "E".repeat(250)),
+        ] {
+            assert!(
+                read_position(&position, &SCRABBLE_VARIANT_OFFICIAL, WordCheck::Exact).is_err()
+            );
+        }
+
+        // the board has to be one a game could get to
+        let board = |middle: &str| {
+            let mut rows = vec!["15"; 15];
+            rows[7] = middle;
+            read_board(
+                &rows.join("/"),
+                &SCRABBLE_VARIANT_OFFICIAL,
+                WordCheck::Exact,
+            )
+        };
+        assert!(board("7CAT5").is_ok());
+        for (middle, error) in [
+            ("7CTA5", "CTA isn't a word"),
+            ("CAT12", "There must be a tile on the centre square"),
+            ("7CAT1AT2", "Some of the tiles aren't joined to the rest"),
+        ] {
+            assert_eq!(board(middle).map(|_| ()), Err(error.to_string()));
+        }
+    }
+
+    #[test]
+    fn test_view_position() {
+        let mut game = two_player_game(WordCheck::Exact, "CASEIR?");
+        let quick = BotConfig {
+            time_limit: Some(Duration::from_millis(50)),
+            ..BotConfig::default()
+        };
+        game.player[0] = Player::new_computer(quick);
+        game.player[1] = Player::new_computer(quick);
+        game.restart_with_seed(2);
+        // near the end the bag is smaller than a rack, and the other player's tiles aren't in it
+        while game.bag.count() >= 7 && !game.is_over {
+            game.computer_move();
+        }
+        assert!(!game.is_over);
+
+        let view = game.view();
+        let position = write_view_position(&view);
+        let read = read_position(&position, &SCRABBLE_VARIANT_OFFICIAL, WordCheck::Exact).unwrap();
+        let read_view = read.view();
+        assert_eq!(read_view.bag_count(), view.bag_count());
+        assert_eq!(read_view.unseen(), view.unseen());
+        assert_eq!(read_view.rack(), view.rack());
+        assert_eq!(read_view.rack_counts(), view.rack_counts());
+        assert_eq!(write_view_position(&read_view), position);
+    }
+}
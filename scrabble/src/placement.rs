@@ -0,0 +1,313 @@
+use crate::board::Board;
+use crate::pos::Position;
+use crate::tiles::{Tile, TileList};
+use crate::{Direction, Game, GameMove, MoveError, TScore};
+
+/*
+Scoring tiles put down anywhere on the board, the way a player lays them out before playing,
+rather than as a starting position, direction and tiles in order.  Everything wrong with the
+play is an error saying exactly what, and a legal play gives the score of each word it makes.
+*/
+
+// a tile put on a square, before it is known what move it is part of
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Placement {
+    pub position: Position,
+    pub tile: Tile,
+}
+
+impl Placement {
+    pub fn new(position: Position, tile: Tile) -> Self {
+        Self { position, tile }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScoredWord {
+    pub word: String,
+    // the first letter of the word
+    pub position: Position,
+    pub direction: Direction,
+    pub score: TScore,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScoredPlacement {
+    // the play as the rest of the engine takes it
+    pub game_move: GameMove,
+    pub main_word: ScoredWord,
+    pub cross_words: Vec<ScoredWord>,
+    pub bingo_bonus: TScore,
+}
+
+impl ScoredPlacement {
+    pub fn score(&self) -> TScore {
+        self.game_move.score
+    }
+}
+
+fn neighbour(pos: Position, direction: Direction, forward: bool) -> Option<Position> {
+    match forward {
+        true => pos.try_step_forward(direction),
+        false => pos.try_step_backward(direction),
+    }
+}
+
+/*
+The word through a square in one direction, with the tiles placed this turn put on the board, and
+the square it starts on.  The engine scores it, this is only to say which word it is.
+*/
+fn word_through(
+    board: &Board,
+    placements: &[Placement],
+    pos: Position,
+    direction: Direction,
+) -> (Position, String) {
+    let tile_at = |pos: Position| {
+        placements
+            .iter()
+            .find(|p| p.position == pos)
+            .map(|p| p.tile)
+            .or_else(|| board.get_cell_pos(pos).tile())
+    };
+    let mut start = pos;
+    while let Some(previous) = neighbour(start, direction, false) {
+        if tile_at(previous).is_none() {
+            break;
+        }
+        start = previous;
+    }
+    let mut word = String::new();
+    let mut current = Some(start);
+    while let Some(letter) = current.and_then(tile_at).and_then(|tile| tile.letter()) {
+        word.push(letter.as_char());
+        current = neighbour(current.unwrap(), direction, true);
+    }
+    (start, word)
+}
+
+/*
+Works out which way the tiles go and checks they are in one line with nothing missing between
+them, other than letters already on the board.  A single tile goes across if there is a letter
+next to it across, otherwise down.
+*/
+fn line_up(board: &Board, placements: &[Placement]) -> Result<(Position, Direction), MoveError> {
+    let first = placements.iter().map(|p| p.position).min().unwrap();
+    let last = placements.iter().map(|p| p.position).max().unwrap();
+    let filled = |pos: Option<Position>| {
+        pos.is_some_and(|pos| board.is_valid_position(pos) && !board.get_cell_pos(pos).is_empty())
+    };
+    let direction = if placements.len() == 1 {
+        match filled(neighbour(first, Direction::Horizontal, false))
+            || filled(neighbour(first, Direction::Horizontal, true))
+        {
+            true => Direction::Horizontal,
+            false => Direction::Vertical,
+        }
+    } else if placements.iter().all(|p| p.position.y == first.y) {
+        Direction::Horizontal
+    } else if placements.iter().all(|p| p.position.x == first.x) {
+        Direction::Vertical
+    } else {
+        return Err(MoveError::InvalidDirection);
+    };
+    let mut pos = first;
+    while pos != last {
+        pos = pos.try_step_forward(direction).unwrap();
+        if !placements.iter().any(|p| p.position == pos) && board.get_cell_pos(pos).is_empty() {
+            return Err(MoveError::BlockingEmptyCellFound);
+        }
+    }
+    Ok((first, direction))
+}
+
+// the first play must cover the centre, after that a tile must be next to one on the board
+fn connects(board: &Board, placements: &[Placement]) -> bool {
+    let variant = board.scrabble_variant;
+    let centre = Position::new(variant.width / 2, variant.height / 2);
+    let board_is_empty = (0..variant.height)
+        .flat_map(|y| (0..variant.width).map(move |x| Position::new(x, y)))
+        .all(|pos| board.get_cell_pos(pos).is_empty());
+    if board_is_empty {
+        return placements.iter().any(|p| p.position == centre);
+    }
+    placements.iter().any(|p| {
+        [Direction::Horizontal, Direction::Vertical]
+            .into_iter()
+            .flat_map(|direction| {
+                [
+                    neighbour(p.position, direction, false),
+                    neighbour(p.position, direction, true),
+                ]
+            })
+            .flatten()
+            .any(|pos| board.is_valid_position(pos) && !board.get_cell_pos(pos).is_empty())
+    })
+}
+
+// the play the current player's tiles make where they have been put, or what is wrong with it
+pub fn score_placements(
+    game: &Game,
+    placements: &[Placement],
+) -> Result<ScoredPlacement, MoveError> {
+    let board = &game.board;
+    if placements.is_empty() {
+        return Err(MoveError::NoTilesPassed);
+    }
+    for (i, placement) in placements.iter().enumerate() {
+        if !board.is_valid_position(placement.position) {
+            return Err(MoveError::InvalidPosition);
+        }
+        placement.tile.try_letter()?;
+        if !board.get_cell_pos(placement.position).is_empty()
+            || placements[..i]
+                .iter()
+                .any(|p| p.position == placement.position)
+        {
+            return Err(MoveError::SquareFilled(placement.position));
+        }
+    }
+    game.current_player()
+        .rack
+        .confirm_contains_tile_list(&TileList(placements.iter().map(|p| p.tile).collect()))?;
+
+    let (start, direction) = line_up(board, placements)?;
+    if !connects(board, placements) {
+        return Err(MoveError::TilesDoNotConnect);
+    }
+    let mut placements = placements.to_vec();
+    placements.sort_by_key(|p| p.position);
+    let tiles = TileList(placements.iter().map(|p| p.tile).collect());
+
+    // the cross words first, so a wrong one is named rather than the letter just not fitting
+    let mut cross_words = vec![];
+    for placement in placements.iter() {
+        let (position, word) = word_through(board, &placements, placement.position, -direction);
+        if word.len() < 2 {
+            continue;
+        }
+        let score = board.score_cross_word(
+            placement.position,
+            -direction,
+            placement.tile,
+            placement.tile.try_letter()?,
+        )?;
+        cross_words.push(ScoredWord {
+            word,
+            position,
+            direction: -direction,
+            score,
+        });
+    }
+    let score = game.validate_move(start, direction, &tiles)?;
+    let bingo_bonus = match placements.len() {
+        7 => board.scrabble_variant.bingo_bonus as TScore,
+        _ => 0,
+    };
+    let (position, word) = word_through(board, &placements, start, direction);
+    let main_word = ScoredWord {
+        word,
+        position,
+        direction,
+        score: score - cross_words.iter().map(|w| w.score).sum::<TScore>() - bingo_bonus,
+    };
+    Ok(ScoredPlacement {
+        game_move: GameMove::new(start, direction, tiles, score),
+        main_word,
+        cross_words,
+        bingo_bonus,
+    })
+}
+
+// the move the tiles make, scored, ready for Game::apply_action
+pub fn placed_move(game: &Game, placements: &[Placement]) -> Result<GameMove, MoveError> {
+    score_placements(game, placements).map(|scored| scored.game_move)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gcg::read_play_placements;
+    use crate::tests::{set_rack, two_player_game};
+    use crate::word_list::WordCheck;
+
+    fn placements(word: &str, x: u8, y: u8, direction: Direction) -> Vec<Placement> {
+        let tiles = TileList::try_from(word).unwrap();
+        let mut pos = Position::new(x, y);
+        let mut placements = vec![];
+        for tile in tiles.0 {
+            placements.push(Placement::new(pos, tile));
+            if let Some(next) = pos.try_step_forward(direction) {
+                pos = next;
+            }
+        }
+        placements
+    }
+
+    #[test]
+    fn test_score_placements() {
+        let mut game = two_player_game(WordCheck::Exact, "CATSEIR");
+        set_rack(&mut game, 1, "TOEDSAB");
+
+        let cats = placements("CATS", 7, 7, Direction::Horizontal);
+        assert_eq!(
+            score_placements(&game, &cats[..3]).map(|p| p.score()),
+            Ok(10)
+        );
+        assert_eq!(
+            score_placements(&game, &placements("CATS", 3, 3, Direction::Horizontal)),
+            Err(MoveError::TilesDoNotConnect)
+        );
+        let mut bent = cats.clone();
+        bent[3].position = Position::new(10, 8);
+        assert_eq!(
+            score_placements(&game, &bent),
+            Err(MoveError::InvalidDirection)
+        );
+        assert_eq!(
+            score_placements(&game, &[cats[0], cats[2]]),
+            Err(MoveError::BlockingEmptyCellFound)
+        );
+        let scored = score_placements(&game, &cats).unwrap();
+        assert_eq!(scored.main_word.word, "CATS");
+        assert_eq!(scored.score(), 12);
+        let game_move = scored.game_move;
+        game.human_move(
+            game_move.starting_position,
+            game_move.direction,
+            &game_move.tiles,
+        )
+        .unwrap();
+
+        assert_eq!(
+            score_placements(&game, &placements("TOE", 9, 7, Direction::Vertical)),
+            Err(MoveError::SquareFilled(Position::new(9, 7)))
+        );
+        // OE under the S of CATS, which also makes SO
+        let oe = placements("OE", 10, 8, Direction::Horizontal);
+        let scored = score_placements(&game, &oe).unwrap();
+        assert_eq!(scored.main_word.word, "OE");
+        let cross_words: Vec<&str> = scored.cross_words.iter().map(|w| w.word.as_str()).collect();
+        assert_eq!(cross_words, vec!["SO"]);
+        assert_eq!(
+            scored.main_word.score + scored.cross_words[0].score,
+            scored.score()
+        );
+        let game_move = scored.game_move;
+        let written = read_play_placements(&game.board, "9K OE").unwrap();
+        assert_eq!(placed_move(&game, &written), Ok(game_move.clone()));
+        let written = read_play_placements(&game.board, "K8 (S)OE").unwrap();
+        assert_eq!(
+            placed_move(&game, &written).map_err(|e| e.to_string()),
+            Err("SOE isn't a word".to_string())
+        );
+        assert_eq!(
+            game.validate_move(
+                game_move.starting_position,
+                game_move.direction,
+                &game_move.tiles
+            ),
+            Ok(game_move.score)
+        );
+    }
+}
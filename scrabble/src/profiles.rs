@@ -0,0 +1,289 @@
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use crate::statistics::{GameStatistics, PlayerStatistics};
+use crate::TScore;
+
+pub const DEFAULT_PROFILES_FILE: &str = "profiles.json";
+pub const INITIAL_RATING: f64 = 1500.0;
+
+// Elo ratings, moving faster for a player's first games
+const PROVISIONAL_GAMES: u32 = 20;
+const PROVISIONAL_K: f64 = 32.0;
+const ESTABLISHED_K: f64 = 16.0;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GameEntry {
+    pub date: String,
+    pub opponents: Vec<String>,
+    pub score: TScore,
+    pub opponent_scores: Vec<TScore>,
+    pub rating_after: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Profile {
+    pub name: String,
+    pub rating: f64,
+    pub games: u32,
+    pub wins: u32,
+    pub losses: u32,
+    pub draws: u32,
+    pub total_points: i64,
+    pub total_spread: i64,
+    pub bingos: u32,
+    pub highest_move: TScore,
+    pub history: Vec<GameEntry>,
+}
+
+impl Profile {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            rating: INITIAL_RATING,
+            games: 0,
+            wins: 0,
+            losses: 0,
+            draws: 0,
+            total_points: 0,
+            total_spread: 0,
+            bingos: 0,
+            highest_move: 0,
+            history: vec![],
+        }
+    }
+
+    pub fn average_score(&self) -> f64 {
+        self.total_points as f64 / self.games.max(1) as f64
+    }
+
+    pub fn average_spread(&self) -> f64 {
+        self.total_spread as f64 / self.games.max(1) as f64
+    }
+
+    fn k_factor(&self) -> f64 {
+        match self.games < PROVISIONAL_GAMES {
+            true => PROVISIONAL_K,
+            false => ESTABLISHED_K,
+        }
+    }
+}
+
+impl Display for Profile {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:16} {:6.0}  {} games {}-{}-{}  average {:.0} spread {:+.0}  {} bingos, highest move {}",
+            self.name,
+            self.rating,
+            self.games,
+            self.wins,
+            self.losses,
+            self.draws,
+            self.average_score(),
+            self.average_spread(),
+            self.bingos,
+            self.highest_move
+        )
+    }
+}
+
+// the chance of a player rated `rating` beating one rated `opponent`
+pub fn expected_score(rating: f64, opponent: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf((opponent - rating) / 400.0))
+}
+
+// a player's change is summed over each of their opponents, as if they were separate games
+pub fn rating_changes(ratings: &[(f64, f64)], scores: &[TScore]) -> Vec<f64> {
+    (0..ratings.len())
+        .map(|i| {
+            let (rating, k) = ratings[i];
+            (0..ratings.len())
+                .filter(|&j| j != i)
+                .map(|j| {
+                    let actual = match scores[i].cmp(&scores[j]) {
+                        std::cmp::Ordering::Greater => 1.0,
+                        std::cmp::Ordering::Equal => 0.5,
+                        std::cmp::Ordering::Less => 0.0,
+                    };
+                    k * (actual - expected_score(rating, ratings[j].0))
+                })
+                .sum()
+        })
+        .collect()
+}
+
+// the profiles file, kept as JSON
+#[derive(Debug, Clone, PartialEq)]
+pub struct Profiles {
+    path: PathBuf,
+    pub profiles: Vec<Profile>,
+}
+
+impl Profiles {
+    // a missing file is no profiles yet
+    pub fn load(path: &Path) -> io::Result<Profiles> {
+        let profiles = match fs::read_to_string(path) {
+            Ok(contents) => serde_json::from_str(&contents)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => vec![],
+            Err(e) => return Err(e),
+        };
+        Ok(Profiles {
+            path: path.to_path_buf(),
+            profiles,
+        })
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        fs::write(
+            &self.path,
+            serde_json::to_string_pretty(&self.profiles).unwrap(),
+        )
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Profile> {
+        self.profiles.iter().find(|p| p.name == name)
+    }
+
+    pub fn add(&mut self, name: &str) -> Result<&Profile, String> {
+        if name.trim().is_empty() {
+            return Err("A profile needs a name".to_string());
+        }
+        if self.get(name).is_some() {
+            return Err(format!("There is already a profile for {}", name));
+        }
+        self.profiles.push(Profile::new(name));
+        Ok(self.profiles.last().unwrap())
+    }
+
+    // highest rated first
+    pub fn ranked(&self) -> Vec<&Profile> {
+        let mut ranked: Vec<&Profile> = self.profiles.iter().collect();
+        ranked.sort_by(|a, b| b.rating.total_cmp(&a.rating));
+        ranked
+    }
+
+    /*
+    Updates the profiles of the players in a finished game, players without a profile are left
+    out of the rating calculation.  A game that was quit, or with the same profile in more than one
+    seat, isn't rated at all.  Returns each rated player's name and rating change.
+    */
+    pub fn record_game(&mut self, stats: &GameStatistics, date: &str) -> Vec<(String, f64)> {
+        if stats.quit {
+            return vec![];
+        }
+        // each rated player's profile and how they did
+        let rated: Vec<(usize, &PlayerStatistics)> = stats
+            .players
+            .iter()
+            .filter_map(|p| {
+                let i = self
+                    .profiles
+                    .iter()
+                    .position(|profile| profile.name == p.name)?;
+                Some((i, p))
+            })
+            .collect();
+        if rated.len() < 2 || rated.iter().map(|(i, _)| i).unique().count() < rated.len() {
+            return vec![];
+        }
+        let ratings: Vec<(f64, f64)> = rated
+            .iter()
+            .map(|&(i, _)| (self.profiles[i].rating, self.profiles[i].k_factor()))
+            .collect();
+        let scores: Vec<TScore> = rated.iter().map(|(_, p)| p.score).collect();
+        let changes = rating_changes(&ratings, &scores);
+
+        let mut result = vec![];
+        for (n, &(i, player)) in rated.iter().enumerate() {
+            let opponents: Vec<&PlayerStatistics> = rated
+                .iter()
+                .filter(|(j, _)| *j != i)
+                .map(|&(_, p)| p)
+                .collect();
+            let best_opponent = opponents.iter().map(|p| p.score).max().unwrap_or(0);
+            let profile = &mut self.profiles[i];
+            profile.rating += changes[n];
+            profile.games += 1;
+            match player.score.cmp(&best_opponent) {
+                std::cmp::Ordering::Greater => profile.wins += 1,
+                std::cmp::Ordering::Equal => profile.draws += 1,
+                std::cmp::Ordering::Less => profile.losses += 1,
+            }
+            profile.total_points += player.score as i64;
+            profile.total_spread += (player.score - best_opponent) as i64;
+            profile.bingos += player.bingos as u32;
+            if let Some(high) = &player.highest {
+                profile.highest_move = profile.highest_move.max(high.score);
+            }
+            profile.history.push(GameEntry {
+                date: date.to_string(),
+                opponents: opponents.iter().map(|p| p.name.clone()).collect(),
+                score: player.score,
+                opponent_scores: opponents.iter().map(|p| p.score).collect(),
+                rating_after: profile.rating,
+            });
+            result.push((profile.name.clone(), changes[n]));
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::SCRABBLE_VARIANT_OFFICIAL;
+    use crate::statistics::game_statistics;
+    use crate::{Game, Player, PlayerType};
+
+    #[test]
+    fn test_record_game() {
+        let mut game = Game::new(
+            &SCRABBLE_VARIANT_OFFICIAL,
+            2,
+            std::array::from_fn(|_| Player::new(PlayerType::Human)),
+            vec!["Ann".to_string(), "Bob".to_string()],
+        );
+        game.player[0].score = 400;
+        game.player[1].score = 350;
+
+        let path =
+            std::env::temp_dir().join(format!("scrabble_profiles_{}.json", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let mut profiles = Profiles::load(&path).unwrap();
+        profiles.add("Ann").unwrap();
+        profiles.add("Bob").unwrap();
+        assert!(profiles.add("Bob").is_err());
+
+        let changes = profiles.record_game(&game_statistics(&game), "2024-01-01");
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].1, 16.0);
+        assert_eq!(changes[1].1, -16.0);
+
+        profiles.save().unwrap();
+        let mut profiles = Profiles::load(&path).unwrap();
+        let ann = profiles.get("Ann").unwrap();
+        assert_eq!((ann.wins, ann.total_spread), (1, 50));
+        assert_eq!(ann.history[0].opponents, vec!["Bob".to_string()]);
+        assert_eq!(profiles.ranked()[0].name, "Ann");
+        let _ = fs::remove_file(&path);
+
+        // playing yourself, or quitting, doesn't change a rating
+        game.player_name[1] = "Ann".to_string();
+        assert!(profiles
+            .record_game(&game_statistics(&game), "2024-01-02")
+            .is_empty());
+        game.player_name[1] = "Bob".to_string();
+        game.quit();
+        assert!(profiles
+            .record_game(&game_statistics(&game), "2024-01-02")
+            .is_empty());
+        assert_eq!(profiles.get("Ann").unwrap().games, 1);
+    }
+}
@@ -0,0 +1,464 @@
+use std::io::{self, BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use web_time::Instant;
+
+use crate::board::Board;
+use crate::board::{ScrabbleVariant, SCRABBLE_VARIANT_OFFICIAL, SCRABBLE_VARIANT_WORDFEUD};
+use crate::bot::{equity, leave_after, leave_value, BotConfig};
+use crate::gcg::{read_play, write_play};
+use crate::notation::{read_position, read_tiles, write_position, write_view_position};
+use crate::strategy::{Action, GameView, Strategy};
+use crate::tiles::TileBag;
+use crate::word_list::WordCheck;
+use crate::{Game, Player, PlayerType, TScore};
+
+/*
+A protocol for driving the engine from another program, after UCI in chess.  Each request is a
+line of JSON on stdin, and each gets exactly one line of JSON back on stdout.
+
+  {"command":"new_game","variant":"official","players":2,"seed":1}
+  {"command":"set_position","position":"15/15/.../15 AEINRST/- 0/0 1"}
+  {"command":"play","move":"8D RETAINS"}
+  {"command":"genmoves","limit":10}
+  {"command":"go","time":2.5}
+  {"command":"position"}
+  {"command":"quit"}
+
+Positions are as notation.rs writes them.  Moves are as in GCG files, so "8D RETAINS" across
+from 8D, "D8 .OgUE" down from D8 through the R already there with a blank for the G, "-ABC" to
+change A, B and C ('?' for a blank) and "-" to pass.  Times are in seconds.
+*/
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VariantName {
+    #[default]
+    Official,
+    Wordfeud,
+}
+
+impl VariantName {
+    pub fn variant(&self) -> &'static ScrabbleVariant {
+        match self {
+            VariantName::Official => &SCRABBLE_VARIANT_OFFICIAL,
+            VariantName::Wordfeud => &SCRABBLE_VARIANT_WORDFEUD,
+        }
+    }
+
+    pub fn of(variant: &ScrabbleVariant) -> Self {
+        match *variant == SCRABBLE_VARIANT_WORDFEUD {
+            true => VariantName::Wordfeud,
+            false => VariantName::Official,
+        }
+    }
+}
+
+fn two_players() -> usize {
+    2
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum Request {
+    NewGame {
+        #[serde(default)]
+        variant: VariantName,
+        #[serde(default = "two_players")]
+        players: usize,
+        seed: Option<u64>,
+    },
+    // the variant is kept from the last game if it's left out
+    SetPosition {
+        position: String,
+        variant: Option<VariantName>,
+    },
+    Play {
+        #[serde(rename = "move")]
+        play: String,
+    },
+    // the legal plays for the player to move, best first
+    Genmoves {
+        limit: Option<usize>,
+        time: Option<f64>,
+    },
+    // what the engine would do, the game is left as it is
+    Go {
+        time: Option<f64>,
+    },
+    Position,
+    Quit,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Evaluation {
+    #[serde(rename = "move")]
+    pub play: String,
+    pub score: TScore,
+    // the score plus what the tiles kept are worth
+    pub equity: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "response", rename_all = "snake_case")]
+pub enum Response {
+    Position { position: String, is_over: bool },
+    Moves { moves: Vec<Evaluation> },
+    BestMove { evaluation: Evaluation },
+    Error { message: String },
+    Bye,
+}
+
+impl Response {
+    fn error(message: impl Into<String>) -> Self {
+        Response::Error {
+            message: message.into(),
+        }
+    }
+}
+
+pub fn write_action(board: &Board, action: &Action) -> String {
+    match action {
+        Action::Play(game_move) => write_play(board, game_move),
+        Action::Exchange(tiles) => format!("-{}", tiles),
+        Action::Pass => "-".to_string(),
+    }
+}
+
+pub fn read_action(board: &Board, s: &str) -> Result<Action, String> {
+    match s.trim() {
+        "-" => Ok(Action::Pass),
+        s if s.starts_with('-') => Ok(Action::Exchange(read_tiles(&s[1..])?.into())),
+        s => Ok(Action::Play(read_play(board, s)?)),
+    }
+}
+
+fn evaluate(board: &Board, rack: &TileBag, action: &Action) -> Evaluation {
+    let (score, equity) = match action {
+        Action::Play(game_move) => (game_move.score, equity(rack, game_move)),
+        Action::Exchange(tiles) => (0, leave_value(&leave_after(rack, tiles))),
+        Action::Pass => (0, leave_value(rack)),
+    };
+    Evaluation {
+        play: write_action(board, action),
+        score,
+        equity,
+    }
+}
+
+fn seconds(time: Option<f64>) -> Result<Option<Duration>, String> {
+    time.map(|t| Duration::try_from_secs_f64(t).map_err(|_| format!("Invalid time {}", t)))
+        .transpose()
+}
+
+// the engine's side of the protocol, it plays as the bot for go
+#[derive(Debug, Clone)]
+pub struct Engine {
+    game: Option<Game>,
+    variant: VariantName,
+    bot: BotConfig,
+}
+
+impl Engine {
+    pub fn new(bot: BotConfig) -> Self {
+        Self {
+            game: None,
+            variant: VariantName::default(),
+            bot,
+        }
+    }
+
+    pub fn game(&self) -> Option<&Game> {
+        self.game.as_ref()
+    }
+
+    fn position(game: &Game) -> Response {
+        Response::Position {
+            position: write_position(game),
+            is_over: game.is_over,
+        }
+    }
+
+    pub fn handle(&mut self, request: Request) -> Response {
+        match self.try_handle(request) {
+            Ok(response) => response,
+            Err(message) => Response::error(message),
+        }
+    }
+
+    fn try_handle(&mut self, request: Request) -> Result<Response, String> {
+        match request {
+            Request::NewGame {
+                variant,
+                players,
+                seed,
+            } => {
+                if !(2..=4).contains(&players) {
+                    return Err("There must be between 2 and 4 players".to_string());
+                }
+                let mut game = Game::new(
+                    variant.variant(),
+                    players,
+                    std::array::from_fn(|_| Player::new(PlayerType::Human)),
+                    (0..players).map(|i| format!("Player {}", i + 1)).collect(),
+                );
+                if let Some(seed) = seed {
+                    game.restart_with_seed(seed);
+                }
+                self.variant = variant;
+                Ok(Self::position(self.game.insert(game)))
+            }
+            Request::SetPosition { position, variant } => {
+                let variant = variant.unwrap_or(self.variant);
+                let game = read_position(&position, variant.variant(), WordCheck::Exact)?;
+                self.variant = variant;
+                Ok(Self::position(self.game.insert(game)))
+            }
+            Request::Quit => Ok(Response::Bye),
+            request => {
+                let game = self.game.as_mut().ok_or("There is no game yet")?;
+                if game.is_over && !matches!(request, Request::Position) {
+                    return Err("The game is over".to_string());
+                }
+                match request {
+                    Request::Play { play } => {
+                        let action = read_action(&game.board, &play)?;
+                        game.apply_action(&action)
+                            .map_err(|e| format!("Can't play {}: {:?}", play, e))?;
+                        Ok(Self::position(game))
+                    }
+                    Request::Genmoves { limit, time } => {
+                        let deadline = seconds(time)?.map(|t| Instant::now() + t);
+                        let rack = game.current_player().rack;
+                        let mut moves: Vec<Evaluation> = game
+                            .legal_moves(deadline)
+                            .into_iter()
+                            .map(|m| evaluate(&game.board, &rack, &Action::Play(m)))
+                            .collect();
+                        moves.sort_by(|a, b| b.equity.total_cmp(&a.equity));
+                        moves.truncate(limit.unwrap_or(usize::MAX));
+                        Ok(Response::Moves { moves })
+                    }
+                    Request::Go { time } => {
+                        let mut bot = self.bot;
+                        if let Some(time) = seconds(time)? {
+                            bot.time_limit = Some(time);
+                        }
+                        let action = bot.choose_action(&game.view());
+                        Ok(Response::BestMove {
+                            evaluation: evaluate(&game.board, &game.current_player().rack, &action),
+                        })
+                    }
+                    _ => Ok(Self::position(game)),
+                }
+            }
+        }
+    }
+}
+
+// answers requests a line at a time until quit or the end of the input
+pub fn run_engine(input: impl BufRead, mut output: impl Write, bot: BotConfig) -> io::Result<()> {
+    let mut engine = Engine::new(bot);
+    for line in input.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let response = match serde_json::from_str::<Request>(&line) {
+            Ok(request) => engine.handle(request),
+            Err(e) => Response::error(format!("Invalid request: {}", e)),
+        };
+        writeln!(output, "{}", serde_json::to_string(&response).unwrap())?;
+        output.flush()?;
+        if response == Response::Bye {
+            break;
+        }
+    }
+    Ok(())
+}
+
+#[derive(Debug)]
+struct EngineProcess {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+}
+
+impl EngineProcess {
+    fn request(&mut self, request: &Request) -> Result<Response, String> {
+        let line = serde_json::to_string(request).unwrap();
+        writeln!(self.stdin, "{}", line).map_err(|e| e.to_string())?;
+        self.stdin.flush().map_err(|e| e.to_string())?;
+        let mut reply = String::new();
+        match self.stdout.read_line(&mut reply) {
+            Ok(0) => Err("The engine has stopped".to_string()),
+            Ok(_) => serde_json::from_str(&reply).map_err(|e| e.to_string()),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+}
+
+/*
+A player run as another program speaking the protocol, which could be written in any language.
+The program is started the first time it is asked for a move, and is given the position as the
+player sees it each turn.  Anything going wrong with it is a pass, and the error is kept for the
+caller to pick up with take_errors.
+*/
+#[derive(Debug)]
+pub struct ExternalStrategy {
+    command: String,
+    args: Vec<String>,
+    time_limit: Option<Duration>,
+    process: Mutex<Option<EngineProcess>>,
+    errors: Mutex<Vec<String>>,
+}
+
+impl ExternalStrategy {
+    // the program and its arguments separated by spaces
+    pub fn new(command_line: &str) -> Self {
+        let mut words = command_line.split_whitespace().map(|w| w.to_string());
+        Self {
+            command: words.next().unwrap_or_default(),
+            args: words.collect(),
+            time_limit: None,
+            process: Mutex::new(None),
+            errors: Mutex::new(vec![]),
+        }
+    }
+
+    pub fn with_time_limit(mut self, time_limit: Duration) -> Self {
+        self.time_limit = Some(time_limit);
+        self
+    }
+
+    // what went wrong since they were last taken, each one cost a turn
+    pub fn take_errors(&self) -> Vec<String> {
+        std::mem::take(&mut self.errors.lock().unwrap())
+    }
+
+    fn start(&self) -> Result<EngineProcess, String> {
+        let mut child = Command::new(&self.command)
+            .args(&self.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("Could not start {}: {}", self.command, e))?;
+        let stdin = child.stdin.take().unwrap();
+        let stdout = BufReader::new(child.stdout.take().unwrap());
+        Ok(EngineProcess {
+            child,
+            stdin,
+            stdout,
+        })
+    }
+
+    fn ask(&self, view: &GameView) -> Result<Action, String> {
+        let mut process = self.process.lock().unwrap();
+        if process.is_none() {
+            *process = Some(self.start()?);
+        }
+        let engine = process.as_mut().unwrap();
+        let set_position = Request::SetPosition {
+            position: write_view_position(view),
+            variant: Some(VariantName::of(view.scrabble_variant())),
+        };
+        if let Response::Error { message } = engine.request(&set_position)? {
+            return Err(message);
+        }
+        let go = Request::Go {
+            time: self.time_limit.map(|t| t.as_secs_f64()),
+        };
+        match engine.request(&go)? {
+            Response::BestMove { evaluation } => read_action(view.board(), &evaluation.play),
+            Response::Error { message } => Err(message),
+            response => Err(format!("Unexpected response {:?}", response)),
+        }
+    }
+}
+
+impl Strategy for ExternalStrategy {
+    fn name(&self) -> String {
+        format!("External ({})", self.command)
+    }
+
+    fn choose_action(&self, view: &GameView) -> Action {
+        match self.ask(view) {
+            Ok(action) => action,
+            Err(e) => {
+                self.errors.lock().unwrap().push(e);
+                Action::Pass
+            }
+        }
+    }
+}
+
+impl Drop for ExternalStrategy {
+    fn drop(&mut self) {
+        if let Some(mut engine) = self.process.get_mut().unwrap().take() {
+            let _ = engine.request(&Request::Quit);
+            let _ = engine.child.kill();
+            let _ = engine.child.wait();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bot::{BotLevel, Persona};
+
+    #[test]
+    fn test_engine() {
+        let input = [
+            r#"{"command":"genmoves"}"#,
+            r#"{"command":"new_game","seed":3}"#,
+            r#"{"command":"set_position","position":"15/15/15/15/15/15/15/15/15/15/15/15/15/15/15 AEINRST/- 0/0 1"}"#,
+            r#"{"command":"genmoves","limit":3}"#,
+            "not json",
+            r#"{"command":"go"}"#,
+            r#"{"command":"play","move":"8D RETAINS"}"#,
+            r#"{"command":"play","move":"-"}"#,
+            r#"{"command":"quit"}"#,
+            r#"{"command":"position"}"#,
+        ]
+        .join("\n");
+        let mut output = vec![];
+        let bot = BotConfig::new(BotLevel::Expert, Persona::Standard);
+        run_engine(input.as_bytes(), &mut output, bot).unwrap();
+        let responses: Vec<Response> = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+
+        // nothing is answered after quit
+        assert_eq!(responses.len(), 9);
+        assert!(matches!(responses[0], Response::Error { .. }));
+        assert!(matches!(responses[1], Response::Position { .. }));
+        let Response::Moves { moves } = &responses[3] else {
+            panic!("{:?}", responses[3]);
+        };
+        assert_eq!(moves.len(), 3);
+        assert_eq!(moves[0].score, 66);
+        assert!(matches!(responses[4], Response::Error { .. }));
+        let Response::BestMove { evaluation } = &responses[5] else {
+            panic!("{:?}", responses[5]);
+        };
+        assert_eq!(evaluation.score, 66);
+        let Response::Position { position, .. } = &responses[7] else {
+            panic!("{:?}", responses[7]);
+        };
+        assert!(position.starts_with("15/15/15/15/15/15/15/3RETAINS5/"));
+        assert!(position.ends_with(" 1"));
+        assert_eq!(responses[8], Response::Bye);
+
+        let board = Board::new(&SCRABBLE_VARIANT_OFFICIAL, WordCheck::Exact);
+        for action in ["-", "-AB?", "8D RETAINS", "H4 ACHIeVE"] {
+            let read = read_action(&board, action).unwrap();
+            assert_eq!(write_action(&board, &read), action);
+        }
+    }
+}
@@ -0,0 +1,357 @@
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::analysis::FoundMove;
+use crate::board::ScrabbleVariant;
+use crate::bot::{best_exchange, equity};
+use crate::notation::{board_tiles, read_position, write_board, write_view_position};
+use crate::pos::Position;
+use crate::protocol::VariantName;
+use crate::strategy::Action;
+use crate::tiles::{TileBag, TileList};
+use crate::word_list::WordCheck;
+use crate::{
+    Direction, Game, GameMove, GameMoveRecordDetail, MoveError, Player, PlayerType, TScore,
+};
+
+pub const DEFAULT_PUZZLES_FILE: &str = "puzzles.json";
+// other moves within this much of the answer make a puzzle harder
+const CLOSE_MARGIN: f32 = 5.0;
+const MAX_GAMES_PER_PUZZLE: u64 = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PuzzleKind {
+    BestScore,
+    BestEquity,
+    Bingo,
+    // the bag is empty and only one play uses all the tiles
+    OutPlay,
+}
+
+impl PuzzleKind {
+    pub const ALL: [PuzzleKind; 4] = [
+        PuzzleKind::BestScore,
+        PuzzleKind::BestEquity,
+        PuzzleKind::Bingo,
+        PuzzleKind::OutPlay,
+    ];
+}
+
+impl Display for PuzzleKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PuzzleKind::BestScore => write!(f, "Find the highest scoring play"),
+            PuzzleKind::BestEquity => write!(f, "Find the best play, counting the tiles kept"),
+            PuzzleKind::Bingo => write!(f, "Find a play using all seven tiles"),
+            PuzzleKind::OutPlay => write!(f, "Find the play which goes out"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Puzzle {
+    pub kind: PuzzleKind,
+    // see notation::write_view_position, the other players' tiles aren't part of the puzzle
+    pub position: String,
+    #[serde(default)]
+    pub variant: VariantName,
+    #[serde(default)]
+    pub word_check: WordCheck,
+    pub rack: String,
+    // the score, or equity for BestEquity, a play has to reach
+    pub answer: f32,
+    pub solutions: Vec<FoundMove>,
+    // how many other plays came close
+    pub alternatives: usize,
+    // from 1 to 5
+    pub difficulty: u8,
+}
+
+impl Display for Puzzle {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} with {} (difficulty {})",
+            self.kind, self.rack, self.difficulty
+        )
+    }
+}
+
+fn difficulty(alternatives: usize) -> u8 {
+    match alternatives {
+        0 => 1,
+        1..=2 => 2,
+        3..=5 => 3,
+        6..=10 => 4,
+        _ => 5,
+    }
+}
+
+/*
+Works out which moves solve each kind of puzzle, and makes a puzzle from each kind that has an
+answer in this position.  The moves must be all the legal ones, a search cut short could miss the
+answer and leave a puzzle with the wrong one.
+*/
+fn make_puzzles(game: &Game, moves: &[GameMove], kinds: &[PuzzleKind]) -> Vec<Puzzle> {
+    let rack = game.current_player().rack;
+    let mut puzzles = vec![];
+    for &kind in kinds {
+        let value = |m: &GameMove| match kind {
+            PuzzleKind::BestEquity => equity(&rack, m),
+            _ => m.score as f32,
+        };
+        let is_solution: Box<dyn Fn(&GameMove) -> bool> = match kind {
+            PuzzleKind::BestScore => {
+                let best = moves.iter().map(|m| m.score).max();
+                Box::new(move |m| Some(m.score) == best)
+            }
+            PuzzleKind::BestEquity => {
+                let best = moves.iter().map(value).fold(f32::MIN, f32::max);
+                Box::new(move |m| equity(&rack, m) >= best - 0.5)
+            }
+            PuzzleKind::Bingo => Box::new(|m| m.tiles.len() == 7),
+            PuzzleKind::OutPlay => {
+                if !game.bag.is_empty() {
+                    continue;
+                }
+                Box::new(|m| m.tiles.len() == rack.count() as usize)
+            }
+        };
+        let solutions: Vec<&GameMove> = moves.iter().filter(|m| is_solution(m)).collect();
+        if solutions.is_empty() || (kind == PuzzleKind::OutPlay && solutions.len() > 1) {
+            continue;
+        }
+        let answer = solutions.iter().map(|m| value(m)).fold(f32::MIN, f32::max);
+        let alternatives = moves
+            .iter()
+            .filter(|m| !is_solution(m) && value(m) >= answer - CLOSE_MARGIN)
+            .count();
+        puzzles.push(Puzzle {
+            kind,
+            position: write_view_position(&game.view()),
+            variant: VariantName::of(game.scrabble_variant()),
+            word_check: game.word_check(),
+            rack: rack.to_string(),
+            answer,
+            solutions: solutions
+                .iter()
+                .map(|m| FoundMove::new(game, &rack, m))
+                .collect(),
+            alternatives,
+            difficulty: difficulty(alternatives),
+        });
+    }
+    puzzles
+}
+
+// the puzzles for the player to move
+pub fn puzzles_from_position(game: &Game, kinds: &[PuzzleKind]) -> Vec<Puzzle> {
+    let moves = game.legal_moves(None);
+    make_puzzles(game, &moves, kinds)
+}
+
+/*
+The puzzles met at each turn of a game, as read by gcg::read_gcg.  Only the rack of the player to
+move is known, so the other racks are just counted, with as many of the tiles that player can't see
+as will fit, and the rest are in the bag.
+*/
+pub fn puzzles_from_game(game: &Game, kinds: &[PuzzleKind]) -> Result<Vec<Puzzle>, String> {
+    let variant = game.scrabble_variant();
+    let mut replay = game.replay();
+    let mut puzzles = vec![];
+    // added up from the record, the replay ends the game when a rack is emptied
+    let mut scores: Vec<TScore> = vec![0; game.number_of_players];
+    for (turn, record) in game.moves.iter().enumerate() {
+        replay.replay_turn(record);
+        let mut unseen = TileBag::new(variant)
+            .count()
+            .saturating_sub(board_tiles(&replay.board).count() + record.rack.count());
+        let racks: Vec<String> = (0..replay.number_of_players)
+            .map(|i| match i == record.player {
+                true => record.rack.to_string(),
+                false => {
+                    let count = unseen.min(7);
+                    unseen -= count;
+                    count.to_string()
+                }
+            })
+            .collect();
+        let position = format!(
+            "{} {} {} {}",
+            write_board(&replay.board),
+            racks.join("/"),
+            scores
+                .iter()
+                .map(|score| score.to_string())
+                .collect::<Vec<_>>()
+                .join("/"),
+            record.player + 1
+        );
+        let position_game = read_position(&position, variant, game.word_check())
+            .map_err(|e| format!("Turn {}: {}", turn + 1, e))?;
+        puzzles.extend(puzzles_from_position(&position_game, kinds));
+        if let GameMoveRecordDetail::Move { score, .. } = record.detail {
+            scores[record.player] += score;
+        }
+        replay.replay_record(record);
+    }
+    Ok(puzzles)
+}
+
+/*
+Plays games against itself, taking the best play by equity each turn, and keeps the puzzles met on
+the way.  The seed fixes the tiles drawn.
+*/
+pub fn self_play_puzzles(
+    count: usize,
+    kinds: &[PuzzleKind],
+    seed: u64,
+    scrabble_variant: &'static ScrabbleVariant,
+) -> Vec<Puzzle> {
+    let mut puzzles = vec![];
+    let mut game = Game::new(
+        scrabble_variant,
+        2,
+        std::array::from_fn(|_| Player::new(PlayerType::Human)),
+        vec!["Player 1".to_string(), "Player 2".to_string()],
+    );
+    // a kind of puzzle which hardly ever comes up mustn't go on forever
+    for seed in seed..seed + count as u64 * MAX_GAMES_PER_PUZZLE {
+        if puzzles.len() >= count {
+            break;
+        }
+        game.restart_with_seed(seed);
+        while !game.is_over && puzzles.len() < count {
+            let rack = game.current_player().rack;
+            let moves = game.legal_moves(None);
+            puzzles.extend(make_puzzles(&game, &moves, kinds));
+            let best = moves
+                .into_iter()
+                .max_by(|a, b| equity(&rack, a).total_cmp(&equity(&rack, b)));
+            let action = match (best, best_exchange(&rack)) {
+                (Some(best), _) => Action::Play(best),
+                (None, Some(exchange)) if game.bag.count() >= 7 => Action::Exchange(exchange.tiles),
+                _ => Action::Pass,
+            };
+            if game.apply_action(&action).is_err() {
+                game.pass();
+            }
+        }
+    }
+    puzzles.truncate(count);
+    puzzles
+}
+
+impl Puzzle {
+    pub fn game(&self) -> Result<Game, String> {
+        read_position(&self.position, self.variant.variant(), self.word_check)
+    }
+
+    /*
+    Checks the play is legal in the puzzle's game, as Game::human_move does, and then whether it
+    reaches the answer.
+    */
+    pub fn check_answer(
+        &self,
+        game: &Game,
+        starting_position: Position,
+        direction: Direction,
+        tiles: &TileList,
+    ) -> Result<bool, MoveError> {
+        let rack = game.current_player().rack;
+        rack.confirm_contains_tile_list(tiles)?;
+        let (min_tiles, max_tiles) = game.validate_position(starting_position, direction)?;
+        if tiles.len() < min_tiles as usize || tiles.len() > max_tiles as usize {
+            return Err(MoveError::TilesDonNotFit);
+        }
+        let score = game.validate_move(starting_position, direction, tiles)?;
+        let game_move = GameMove::new(starting_position, direction, tiles.clone(), score);
+        Ok(match self.kind {
+            PuzzleKind::BestScore => score as f32 >= self.answer,
+            PuzzleKind::BestEquity => equity(&rack, &game_move) >= self.answer - 0.5,
+            PuzzleKind::Bingo => tiles.len() == 7,
+            PuzzleKind::OutPlay => tiles.len() == rack.count() as usize,
+        })
+    }
+}
+
+pub fn load_puzzles(path: &Path) -> io::Result<Vec<Puzzle>> {
+    serde_json::from_str(&fs::read_to_string(path)?)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+pub fn save_puzzles(path: &Path, puzzles: &[Puzzle]) -> io::Result<()> {
+    fs::write(path, serde_json::to_string_pretty(puzzles).unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::SCRABBLE_VARIANT_OFFICIAL;
+    use crate::gcg::read_gcg;
+
+    #[test]
+    fn test_bingo_puzzle() {
+        let position = "15/15/15/15/15/15/15/15/15/15/15/15/15/15/15 AEINRST/DEGOORU 0/0 1";
+        let game = read_position(position, &SCRABBLE_VARIANT_OFFICIAL, WordCheck::Exact).unwrap();
+        let puzzles = puzzles_from_position(&game, &PuzzleKind::ALL);
+        let kinds: Vec<PuzzleKind> = puzzles.iter().map(|p| p.kind).collect();
+        assert!(kinds.contains(&PuzzleKind::BestScore));
+        assert!(!kinds.contains(&PuzzleKind::OutPlay));
+
+        let bingo = puzzles
+            .iter()
+            .find(|p| p.kind == PuzzleKind::Bingo)
+            .unwrap();
+        assert!(bingo.solutions.iter().any(|s| s.word == "RETAINS"));
+        let game = bingo.game().unwrap();
+        let start = Position::new(7, 7);
+        let retains = TileList::try_from("RETAINS").unwrap();
+        let at = TileList::try_from("AT").unwrap();
+        assert_eq!(
+            bingo.check_answer(&game, start, Direction::Horizontal, &retains),
+            Ok(true)
+        );
+        assert_eq!(
+            bingo.check_answer(&game, start, Direction::Horizontal, &at),
+            Ok(false)
+        );
+        assert!(bingo
+            .check_answer(
+                &game,
+                start,
+                Direction::Horizontal,
+                &TileList::try_from("ZA").unwrap()
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn test_game_puzzles() {
+        let gcg = "#player1 Ann Ann\n\
+                   #player2 Bob Bob\n\
+                   >Ann: AEINRST 8D RETAINS +72 72\n\
+                   >Bob: DEOORU? D8 .OgUE +12 12\n";
+        let game = read_gcg(gcg, &SCRABBLE_VARIANT_OFFICIAL, WordCheck::Exact).unwrap();
+        let puzzles = puzzles_from_game(&game, &PuzzleKind::ALL).unwrap();
+        assert!(puzzles
+            .iter()
+            .any(|p| p.kind == PuzzleKind::Bingo && p.rack == "AEINRST"));
+        assert!(puzzles.iter().any(|p| p.rack == "DEOORU?"));
+        assert!(puzzles
+            .iter()
+            .all(|p| p.variant == VariantName::Official && p.word_check == WordCheck::Exact));
+
+        // Bob can't see Ann's rack, so it's made up from the tiles left
+        let puzzle = puzzles.iter().find(|p| p.rack == "DEOORU?").unwrap();
+        let puzzle_game = puzzle.game().unwrap();
+        assert_eq!(puzzle_game.player[0].score, 72);
+        assert_eq!(puzzle_game.current_player, 1);
+        assert_eq!(puzzle_game.player[0].rack.count(), 7);
+        assert_eq!(puzzle_game.bag.count(), 100 - 7 - 7 - 7);
+    }
+}
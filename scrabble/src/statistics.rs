@@ -0,0 +1,250 @@
+use std::fmt::{Display, Formatter};
+
+use serde::Serialize;
+
+use crate::board::CellType;
+use crate::tiles::{Letter, Tile};
+use crate::{Game, GameMove, GameMoveRecordDetail, TScore};
+
+// the premium squares covered by a player's tiles
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct PremiumUsage {
+    pub double_letter: usize,
+    pub triple_letter: usize,
+    pub double_word: usize,
+    pub triple_word: usize,
+}
+
+impl PremiumUsage {
+    fn add(&mut self, cell_type: CellType) {
+        match cell_type {
+            CellType::DoubleLetter => self.double_letter += 1,
+            CellType::TripleLetter => self.triple_letter += 1,
+            CellType::DoubleWord => self.double_word += 1,
+            CellType::TripleWord => self.triple_word += 1,
+            CellType::Blank => {}
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HighMove {
+    pub player: usize,
+    pub word: String,
+    pub score: TScore,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PlayerStatistics {
+    pub name: String,
+    pub score: TScore,
+    pub turns: usize,
+    pub moves: usize,
+    pub exchanges: usize,
+    pub passes: usize,
+    pub points: i32,
+    pub average_per_turn: f64,
+    pub bingos: usize,
+    pub highest: Option<HighMove>,
+    pub blanks_played: usize,
+    pub s_played: usize,
+    pub premium: PremiumUsage,
+    // how many tiles went back to the bag or onto the board
+    pub tiles_played: usize,
+    pub tiles_exchanged: usize,
+    pub seconds: f64,
+    pub seconds_per_turn: f64,
+}
+
+impl PlayerStatistics {
+    pub fn tile_turnover(&self) -> usize {
+        self.tiles_played + self.tiles_exchanged
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GameStatistics {
+    pub turns: usize,
+    pub bingos: usize,
+    pub highest: Option<HighMove>,
+    pub players: Vec<PlayerStatistics>,
+    // the game was quit before the end
+    pub quit: bool,
+}
+
+impl GameStatistics {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+}
+
+fn higher(a: Option<HighMove>, b: Option<HighMove>) -> Option<HighMove> {
+    match (a, b) {
+        (Some(a), Some(b)) if b.score > a.score => Some(b),
+        (a, b) => a.or(b),
+    }
+}
+
+// goes through the recorded moves, replaying them to find the squares each tile went on
+pub fn game_statistics(game: &Game) -> GameStatistics {
+    let mut players: Vec<PlayerStatistics> = (0..game.number_of_players)
+        .map(|i| PlayerStatistics {
+            name: game.player_name[i].clone(),
+            score: game.player[i].score,
+            turns: 0,
+            moves: 0,
+            exchanges: 0,
+            passes: 0,
+            points: 0,
+            average_per_turn: 0.0,
+            bingos: 0,
+            highest: None,
+            blanks_played: 0,
+            s_played: 0,
+            premium: PremiumUsage::default(),
+            tiles_played: 0,
+            tiles_exchanged: 0,
+            seconds: game.player[i].timer.elapsed().as_secs_f64(),
+            seconds_per_turn: 0.0,
+        })
+        .collect();
+
+    let mut replay = game.replay();
+    for record in game.moves.iter() {
+        let stats = &mut players[record.player];
+        stats.turns += 1;
+        match &record.detail {
+            GameMoveRecordDetail::Move {
+                starting_position,
+                direction,
+                tiles,
+                score,
+                word,
+            } => {
+                stats.moves += 1;
+                stats.points += *score as i32;
+                stats.tiles_played += tiles.len();
+                if tiles.len() == 7 {
+                    stats.bingos += 1;
+                }
+                stats.highest = higher(
+                    stats.highest.take(),
+                    Some(HighMove {
+                        player: record.player,
+                        word: word.clone(),
+                        score: *score,
+                    }),
+                );
+                for tile in tiles.0.iter() {
+                    match tile {
+                        Tile::Blank { .. } => stats.blanks_played += 1,
+                        Tile::Letter(letter) if *letter == Letter::from('S') => stats.s_played += 1,
+                        Tile::Letter(_) => {}
+                    }
+                }
+                let game_move =
+                    GameMove::new(*starting_position, *direction, tiles.clone(), *score);
+                for position in game_move.tile_positions(&replay.board) {
+                    stats
+                        .premium
+                        .add(replay.board.get_cell_pos(position).cell_type);
+                }
+            }
+            GameMoveRecordDetail::Exchange { tiles } => {
+                stats.exchanges += 1;
+                stats.tiles_exchanged += tiles.len();
+            }
+            GameMoveRecordDetail::Pass => stats.passes += 1,
+        }
+        replay.replay_record(record);
+    }
+
+    for stats in players.iter_mut() {
+        let turns = stats.turns.max(1) as f64;
+        stats.average_per_turn = stats.points as f64 / turns;
+        stats.seconds_per_turn = stats.seconds / turns;
+    }
+    GameStatistics {
+        turns: game.moves.len(),
+        bingos: players.iter().map(|p| p.bingos).sum(),
+        highest: players
+            .iter()
+            .fold(None, |high, p| higher(high, p.highest.clone())),
+        players,
+        quit: game.was_quit,
+    }
+}
+
+impl Display for GameStatistics {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{} turns, {} bingos", self.turns, self.bingos)?;
+        if let Some(high) = &self.highest {
+            writeln!(
+                f,
+                "Highest move {} for {} by {}",
+                high.word, high.score, self.players[high.player].name
+            )?;
+        }
+        for p in self.players.iter() {
+            writeln!(f)?;
+            writeln!(f, "{}: {} points", p.name, p.score)?;
+            writeln!(
+                f,
+                "  {} turns, {:.1} points per turn, {} bingos, {} exchanges, {} passes",
+                p.turns, p.average_per_turn, p.bingos, p.exchanges, p.passes
+            )?;
+            if let Some(high) = &p.highest {
+                writeln!(f, "  highest move {} for {}", high.word, high.score)?;
+            }
+            writeln!(
+                f,
+                "  {} blanks and {} S played, {} tiles played and {} exchanged",
+                p.blanks_played, p.s_played, p.tiles_played, p.tiles_exchanged
+            )?;
+            writeln!(
+                f,
+                "  premium squares: {} double letter, {} triple letter, {} double word, {} triple word",
+                p.premium.double_letter,
+                p.premium.triple_letter,
+                p.premium.double_word,
+                p.premium.triple_word
+            )?;
+            writeln!(
+                f,
+                "  {:.1} seconds, {:.1} per turn",
+                p.seconds, p.seconds_per_turn
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pos::Position;
+    use crate::tests::two_player_game;
+    use crate::tiles::TileList;
+    use crate::word_list::WordCheck;
+    use crate::Direction;
+
+    #[test]
+    fn test_game_statistics() {
+        let mut game = two_player_game(WordCheck::Exact, "CATSEIR");
+        let tiles = TileList::try_from("CATS").unwrap();
+        game.human_move(Position::new(7, 7), Direction::Horizontal, &tiles)
+            .unwrap();
+        game.pass();
+
+        let stats = game_statistics(&game);
+        assert_eq!(stats.turns, 2);
+        let first = &stats.players[0];
+        assert_eq!(first.moves, 1);
+        assert_eq!(first.points, 12);
+        assert_eq!(first.s_played, 1);
+        assert_eq!(first.premium.double_word, 1);
+        assert_eq!(first.tile_turnover(), 4);
+        assert_eq!(stats.players[1].passes, 1);
+        assert_eq!(stats.highest.as_ref().unwrap().word, "CATS");
+    }
+}
@@ -0,0 +1,202 @@
+use std::fmt::{Debug, Display, Formatter};
+use std::time::Duration;
+
+use rand::Rng;
+use web_time::Instant;
+
+use crate::board::{Board, ScrabbleVariant};
+use crate::tiles::{TileBag, TileList};
+use crate::{Game, GameMove, GameMoveRecord, TScore};
+
+// what a player does on their turn
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Action {
+    Play(GameMove),
+    Exchange(TileList),
+    Pass,
+}
+
+impl Display for Action {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Action::Play(game_move) => write!(
+                f,
+                "{} {} {} for {} points",
+                game_move.starting_position, game_move.direction, game_move.tiles, game_move.score
+            ),
+            Action::Exchange(tiles) => write!(f, "Exchange {}", tiles),
+            Action::Pass => write!(f, "Pass"),
+        }
+    }
+}
+
+/*
+The game as the player whose turn it is sees it.  The other players' racks are hidden,
+their tiles are counted in with the bag as unseen tiles.
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct GameView<'a> {
+    game: &'a Game,
+}
+
+impl<'a> GameView<'a> {
+    pub fn new(game: &'a Game) -> Self {
+        Self { game }
+    }
+
+    pub fn scrabble_variant(&self) -> &'static ScrabbleVariant {
+        self.game.scrabble_variant
+    }
+
+    pub fn board(&self) -> &'a Board {
+        &self.game.board
+    }
+
+    pub fn rack(&self) -> TileBag {
+        self.game.current_player().rack
+    }
+
+    // the tiles in the bag and on the other players' racks
+    pub fn unseen(&self) -> TileBag {
+        let mut unseen = self.game.bag;
+        for (i, player) in self.game.player[..self.game.number_of_players]
+            .iter()
+            .enumerate()
+        {
+            if i != self.game.current_player {
+                unseen.add_tile_list(&player.rack.into());
+            }
+        }
+        unseen
+    }
+
+    pub fn bag_count(&self) -> u8 {
+        self.game.bag.count()
+    }
+
+    // how many tiles each player has, which everyone can see even if not which ones
+    pub fn rack_counts(&self) -> Vec<u8> {
+        self.game.player[..self.game.number_of_players]
+            .iter()
+            .map(|player| player.rack.count())
+            .collect()
+    }
+
+    // the index of the player whose turn it is
+    pub fn player(&self) -> usize {
+        self.game.current_player
+    }
+
+    pub fn number_of_players(&self) -> usize {
+        self.game.number_of_players
+    }
+
+    pub fn scores(&self) -> Vec<TScore> {
+        self.game.player[..self.game.number_of_players]
+            .iter()
+            .map(|player| player.score)
+            .collect()
+    }
+
+    pub fn moves(&self) -> &'a [GameMoveRecord] {
+        &self.game.moves
+    }
+
+    // how long the current player has spent thinking this game, including this turn so far
+    pub fn elapsed(&self) -> Duration {
+        self.game.current_player().timer.elapsed()
+    }
+
+    // all the moves the rack can make, see Game::legal_moves
+    pub fn legal_moves(&self, deadline: Option<Instant>) -> Vec<GameMove> {
+        self.game.legal_moves(deadline)
+    }
+
+    /*
+    The best score the next player could reply with after the move, with their rack drawn at
+    random from the unseen tiles.  Once the bag is empty that is their actual rack.
+    */
+    pub fn best_reply(
+        &self,
+        game_move: &GameMove,
+        rng: &mut impl Rng,
+        deadline: Option<Instant>,
+    ) -> TScore {
+        let mut game = self.game.clone();
+        let mut unseen = self.unseen();
+        if game
+            .apply_move(
+                game_move.starting_position,
+                game_move.direction,
+                &game_move.tiles,
+                game_move.score,
+            )
+            .is_err()
+            || game.is_over
+        {
+            return 0;
+        }
+        let mut rack = TileBag::new_empty();
+        rack.fill_rack_with_rng(&mut unseen, rng);
+        game.current_player_mut().rack = rack;
+        game.legal_moves(deadline)
+            .iter()
+            .map(|m| m.score)
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+/*
+A way of playing.  Each player holds their own strategy, which is asked for an action
+whenever it is their turn.  Strategies are shared between threads, so any state they
+keep has to be behind a lock.
+*/
+pub trait Strategy: Debug + Send + Sync {
+    fn name(&self) -> String;
+    fn choose_action(&self, view: &GameView) -> Action;
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::board::SCRABBLE_VARIANT_OFFICIAL;
+    use crate::{GameMoveRecordDetail, Player, PlayerType};
+
+    #[derive(Debug)]
+    struct AlwaysPass;
+
+    impl Strategy for AlwaysPass {
+        fn name(&self) -> String {
+            "Always pass".to_string()
+        }
+
+        fn choose_action(&self, _view: &GameView) -> Action {
+            Action::Pass
+        }
+    }
+
+    #[test]
+    fn test_custom_strategy() {
+        let mut game = Game::new(
+            &SCRABBLE_VARIANT_OFFICIAL,
+            2,
+            std::array::from_fn(|_| Player::with_strategy(Arc::new(AlwaysPass))),
+            vec!["Player 1".to_string(), "Player 2".to_string()],
+        );
+        assert_eq!(game.player[0].player_type, PlayerType::Computer);
+
+        let view = game.view();
+        assert_eq!(view.unseen().count(), 100 - 7);
+        assert_eq!(view.scores(), vec![0, 0]);
+
+        game.computer_move();
+        assert!(matches!(
+            game.last_move().unwrap().detail,
+            GameMoveRecordDetail::Pass
+        ));
+        assert_eq!(game.current_player, 1);
+    }
+}
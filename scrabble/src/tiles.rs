@@ -200,10 +200,11 @@ impl Display for Tile {
             Tile::Letter(letter) => write!(f, "{}", letter),
             Tile::Blank {
                 acting_as_letter: None,
-            } => write!(f, "*"),
+            } => write!(f, "?"),
+            // a blank is written in lower case once it has a letter
             Tile::Blank {
                 acting_as_letter: Some(letter),
-            } => write!(f, "{letter}"),
+            } => write!(f, "{}", letter.as_char().to_ascii_lowercase()),
         }
     }
 }
@@ -215,7 +216,11 @@ impl From<&str> for Tile {
         let second = s[1];
         if first == '*' {
             Tile::Blank {
-                acting_as_letter: Some(second.into()),
+                acting_as_letter: Some(second.to_ascii_uppercase().into()),
+            }
+        } else if first.is_ascii_lowercase() {
+            Tile::Blank {
+                acting_as_letter: Some(first.to_ascii_uppercase().into()),
             }
         } else {
             Tile::Letter(Letter::from(first))
@@ -274,7 +279,7 @@ impl Display for TileBag {
             }
         }
         for _ in 0..self.blanks {
-            write!(f, "*")?;
+            write!(f, "?")?;
         }
         Ok(())
     }
@@ -307,7 +312,7 @@ impl TileBag {
     pub fn contains(&self, letter: Letter) -> bool {
         self.letters[letter.as_usize()] > 0
     }
-    pub(crate) fn count(&self) -> u8 {
+    pub fn count(&self) -> u8 {
         self.letters.iter().sum::<u8>() + self.blanks
     }
     fn count_tile(&self, tile: Tile) -> u8 {
@@ -326,8 +331,7 @@ impl TileBag {
         sum as TScore
     }
 
-    fn random_tile(&self) -> Tile {
-        let mut rng = rand::thread_rng();
+    fn random_tile(&self, rng: &mut impl Rng) -> Tile {
         let count = self.count();
         let random = rng.gen_range(0..count);
         if random < self.blanks {
@@ -382,8 +386,12 @@ impl TileBag {
         self.count() == 0
     }
     pub fn fill_rack(&mut self, bag: &mut TileBag) {
+        self.fill_rack_with_rng(bag, &mut rand::thread_rng());
+    }
+    // draws from the bag using the given random numbers, so a seeded rng always draws the same tiles
+    pub fn fill_rack_with_rng(&mut self, bag: &mut TileBag, rng: &mut impl Rng) {
         while !bag.is_empty() && self.count() < 7 {
-            let tile = bag.random_tile();
+            let tile = bag.random_tile(rng);
             self.add_tile(tile);
             bag.remove_tile(tile);
         }
@@ -452,34 +460,33 @@ impl From<TileList> for TileBag {
     }
 }
 
+/*
+A blank is a lower case letter, as blanks are usually written, or '*' before the letter it stands
+for.  A '?' is a blank which hasn't been given a letter, as on a rack.
+*/
 impl TryFrom<&str> for TileList {
     type Error = crate::MoveError;
 
     fn try_from(s: &str) -> Result<Self, Self::Error> {
         let mut vec = Vec::new();
-        let mut index = 0;
-        let s = s.trim().to_uppercase();
-        while index < s.len() {
-            let c = s.chars().nth(index).unwrap();
-            if c == '*' {
-                if let Some(next) = s.chars().nth(index + 1) {
-                    if next.is_ascii_uppercase() {
-                        vec.push(Tile::Blank {
-                            acting_as_letter: Some(next.into()),
-                        });
-                        index += 1;
-                    } else {
-                        return Err(crate::MoveError::InvalidTile(c));
-                    }
-                } else {
-                    return Err(crate::MoveError::InvalidTile(c));
-                }
-            } else if c.is_ascii_uppercase() {
-                vec.push(Tile::Letter(c.into()));
-            } else {
-                return Err(crate::MoveError::InvalidTile(c));
+        let mut chars = s.trim().chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '*' => match chars.next() {
+                    Some(next) if next.is_ascii_alphabetic() => vec.push(Tile::Blank {
+                        acting_as_letter: Some(next.to_ascii_uppercase().into()),
+                    }),
+                    _ => return Err(crate::MoveError::InvalidTile(c)),
+                },
+                '?' => vec.push(Tile::Blank {
+                    acting_as_letter: None,
+                }),
+                'A'..='Z' => vec.push(Tile::Letter(c.into())),
+                'a'..='z' => vec.push(Tile::Blank {
+                    acting_as_letter: Some(c.to_ascii_uppercase().into()),
+                }),
+                _ => return Err(crate::MoveError::InvalidTile(c)),
             }
-            index += 1;
         }
         Ok(TileList(vec))
     }
@@ -581,4 +588,27 @@ mod tests {
         println!("letter set is: {}", letter_set);
         //assert_eq!(iter.next(), None);
     }
+
+    #[test]
+    fn test_blank_notation() {
+        let blank = |c: char| Tile::Blank {
+            acting_as_letter: Some(Letter::from(c)),
+        };
+        let tiles = TileList::try_from("QUiZ").unwrap();
+        assert_eq!(tiles.0[2], blank('I'));
+        assert_eq!(TileList::try_from("QU*IZ"), Ok(tiles.clone()));
+        assert_eq!(tiles.to_string(), "QUiZ");
+        assert_eq!(
+            TileList::try_from("AB?").unwrap().0[2],
+            Tile::Blank {
+                acting_as_letter: None
+            }
+        );
+        // a blank without a letter is written the way it's read
+        let rack = TileList::try_from("AB?").unwrap();
+        assert_eq!(rack.to_string(), "AB?");
+        assert_eq!(TileBag::from(rack).to_string(), "AB?");
+        assert_eq!(TileList::try_from("A*"), Err(MoveError::InvalidTile('*')));
+        assert_eq!(TileList::try_from("A1"), Err(MoveError::InvalidTile('1')));
+    }
 }
@@ -0,0 +1,428 @@
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::bot::BotConfig;
+use crate::TScore;
+
+pub const DEFAULT_TOURNAMENT_FILE: &str = "tournament.json";
+// a bye counts as a win by this much
+pub const BYE_SPREAD: TScore = 50;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Entrant {
+    pub name: String,
+    // None for a human player
+    pub bot: Option<BotConfig>,
+    // the command for a program speaking the engine protocol, which plays instead of the bot
+    #[serde(default)]
+    pub engine: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Format {
+    RoundRobin,
+    Swiss { rounds: usize },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameScore {
+    pub first: TScore,
+    pub second: TScore,
+    // where the game was saved, if it was
+    pub game_file: Option<String>,
+}
+
+// second is None for a bye
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Pairing {
+    pub first: usize,
+    pub second: Option<usize>,
+    pub score: Option<GameScore>,
+}
+
+impl Pairing {
+    pub fn is_finished(&self) -> bool {
+        self.second.is_none() || self.score.is_some()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Standing {
+    pub entrant: usize,
+    pub name: String,
+    pub played: usize,
+    // a draw is half a win
+    pub wins: f32,
+    pub losses: f32,
+    pub spread: i32,
+    pub firsts: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Tournament {
+    pub name: String,
+    pub format: Format,
+    pub entrants: Vec<Entrant>,
+    pub rounds: Vec<Vec<Pairing>>,
+}
+
+/*
+The round robin schedule by the circle method, the first entrant stays put and the rest go
+round.  With an odd number one of them sits out each round.
+*/
+fn round_robin(entrants: usize) -> Vec<Vec<(usize, Option<usize>)>> {
+    let mut ring: Vec<Option<usize>> = (0..entrants).map(Some).collect();
+    if entrants % 2 == 1 {
+        ring.push(None);
+    }
+    let n = ring.len();
+    let mut rounds = vec![];
+    for _ in 0..n.saturating_sub(1) {
+        let mut round = vec![];
+        for i in 0..n / 2 {
+            match (ring[i], ring[n - 1 - i]) {
+                (Some(a), Some(b)) => round.push((a, Some(b))),
+                (Some(a), None) | (None, Some(a)) => round.push((a, None)),
+                (None, None) => {}
+            }
+        }
+        rounds.push(round);
+        ring[1..].rotate_right(1);
+    }
+    rounds
+}
+
+impl Tournament {
+    pub fn new(name: &str, format: Format, entrants: Vec<Entrant>) -> Result<Self, String> {
+        if entrants.len() < 2 {
+            return Err("A tournament needs at least two players".to_string());
+        }
+        let names: HashSet<&str> = entrants.iter().map(|e| e.name.as_str()).collect();
+        if names.len() != entrants.len() {
+            return Err("Every player needs a different name".to_string());
+        }
+        Ok(Self {
+            name: name.to_string(),
+            format,
+            entrants,
+            rounds: vec![],
+        })
+    }
+
+    pub fn load(path: &Path) -> io::Result<Tournament> {
+        serde_json::from_str(&fs::read_to_string(path)?)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self).unwrap())
+    }
+
+    pub fn total_rounds(&self) -> usize {
+        match self.format {
+            Format::RoundRobin => self.entrants.len() + self.entrants.len() % 2 - 1,
+            Format::Swiss { rounds } => rounds,
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.rounds.len() == self.total_rounds() && self.round_is_finished()
+    }
+
+    // true before the first round as well
+    pub fn round_is_finished(&self) -> bool {
+        self.rounds
+            .last()
+            .is_none_or(|round| round.iter().all(|p| p.is_finished()))
+    }
+
+    fn firsts(&self, entrant: usize) -> usize {
+        self.rounds
+            .iter()
+            .flatten()
+            .filter(|p| p.first == entrant && p.second.is_some())
+            .count()
+    }
+
+    // how many more times the entrant has gone first than second
+    fn first_balance(&self, entrant: usize) -> i32 {
+        self.rounds
+            .iter()
+            .flatten()
+            .map(|p| match (p.first == entrant, p.second) {
+                (true, Some(_)) => 1,
+                (false, Some(second)) if second == entrant => -1,
+                _ => 0,
+            })
+            .sum()
+    }
+
+    fn have_played(&self, a: usize, b: usize) -> bool {
+        self.rounds
+            .iter()
+            .flatten()
+            .any(|p| (p.first == a && p.second == Some(b)) || (p.first == b && p.second == Some(a)))
+    }
+
+    // whoever has gone second more often goes first, if they are level it alternates by round
+    fn balanced(&self, a: usize, b: Option<usize>) -> Pairing {
+        let swap = |b| match self.first_balance(a).cmp(&self.first_balance(b)) {
+            std::cmp::Ordering::Greater => true,
+            std::cmp::Ordering::Equal => self.rounds.len() % 2 == 1,
+            std::cmp::Ordering::Less => false,
+        };
+        let (first, second) = match b {
+            Some(b) if swap(b) => (b, Some(a)),
+            _ => (a, b),
+        };
+        Pairing {
+            first,
+            second,
+            score: None,
+        }
+    }
+
+    /*
+    Pairs the next round, once the last one is finished.  Swiss rounds pair the players in order
+    of the standings, each with the next player down they haven't met, and the bye goes to the
+    lowest placed player who hasn't had one.
+    */
+    pub fn pair_next_round(&mut self) -> Result<&[Pairing], String> {
+        if !self.round_is_finished() {
+            return Err("The current round isn't finished".to_string());
+        }
+        if self.rounds.len() >= self.total_rounds() {
+            return Err("All the rounds have been played".to_string());
+        }
+        let pairs = match self.format {
+            Format::RoundRobin => round_robin(self.entrants.len())[self.rounds.len()].clone(),
+            Format::Swiss { .. } => {
+                let mut order: Vec<usize> = self.standings().iter().map(|s| s.entrant).collect();
+                let mut pairs = vec![];
+                let mut bye = None;
+                if order.len() % 2 == 1 {
+                    let had_bye: HashSet<usize> = self
+                        .rounds
+                        .iter()
+                        .flatten()
+                        .filter(|p| p.second.is_none())
+                        .map(|p| p.first)
+                        .collect();
+                    let i = (0..order.len())
+                        .rev()
+                        .find(|&i| !had_bye.contains(&order[i]))
+                        .unwrap_or(order.len() - 1);
+                    bye = Some(order.remove(i));
+                }
+                while !order.is_empty() {
+                    let a = order.remove(0);
+                    let opponent = (0..order.len())
+                        .find(|&i| !self.have_played(a, order[i]))
+                        .unwrap_or(0);
+                    pairs.push((a, Some(order.remove(opponent))));
+                }
+                pairs.extend(bye.map(|b| (b, None)));
+                pairs
+            }
+        };
+        let round = pairs
+            .into_iter()
+            .map(|(a, b)| self.balanced(a, b))
+            .collect();
+        self.rounds.push(round);
+        Ok(self.rounds.last().unwrap())
+    }
+
+    pub fn record_result(
+        &mut self,
+        round: usize,
+        pairing: usize,
+        score: GameScore,
+    ) -> Result<(), String> {
+        let pairing = self
+            .rounds
+            .get_mut(round)
+            .and_then(|r| r.get_mut(pairing))
+            .ok_or_else(|| "There is no such game".to_string())?;
+        if pairing.second.is_none() {
+            return Err("A bye has no result".to_string());
+        }
+        pairing.score = Some(score);
+        Ok(())
+    }
+
+    // most wins first, then the best spread
+    pub fn standings(&self) -> Vec<Standing> {
+        let mut standings: Vec<Standing> = self
+            .entrants
+            .iter()
+            .enumerate()
+            .map(|(i, e)| Standing {
+                entrant: i,
+                name: e.name.clone(),
+                played: 0,
+                wins: 0.0,
+                losses: 0.0,
+                spread: 0,
+                firsts: self.firsts(i),
+            })
+            .collect();
+        for pairing in self.rounds.iter().flatten() {
+            match (pairing.second, &pairing.score) {
+                (None, _) => {
+                    let s = &mut standings[pairing.first];
+                    s.played += 1;
+                    s.wins += 1.0;
+                    s.spread += BYE_SPREAD as i32;
+                }
+                (Some(second), Some(score)) => {
+                    let spread = (score.first - score.second) as i32;
+                    let result = match spread.signum() {
+                        1 => 1.0,
+                        0 => 0.5,
+                        _ => 0.0,
+                    };
+                    for (entrant, spread, result) in [
+                        (pairing.first, spread, result),
+                        (second, -spread, 1.0 - result),
+                    ] {
+                        let s = &mut standings[entrant];
+                        s.played += 1;
+                        s.wins += result;
+                        s.losses += 1.0 - result;
+                        s.spread += spread;
+                    }
+                }
+                (Some(_), None) => {}
+            }
+        }
+        standings.sort_by(|a, b| {
+            b.wins
+                .total_cmp(&a.wins)
+                .then(b.spread.cmp(&a.spread))
+                .then(a.entrant.cmp(&b.entrant))
+        });
+        standings
+    }
+
+    pub fn standings_csv(&self) -> String {
+        let mut csv = "rank,name,played,wins,losses,spread,firsts\n".to_string();
+        for (i, s) in self.standings().iter().enumerate() {
+            csv.push_str(&format!(
+                "{},{},{},{},{},{},{}\n",
+                i + 1,
+                s.name,
+                s.played,
+                s.wins,
+                s.losses,
+                s.spread,
+                s.firsts
+            ));
+        }
+        csv
+    }
+}
+
+impl Display for Tournament {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{} after {} of {} rounds",
+            self.name,
+            self.rounds.len(),
+            self.total_rounds()
+        )?;
+        writeln!(
+            f,
+            "{:>4} {:20} {:>6} {:>5} {:>6} {:>7}",
+            "", "Name", "Played", "Wins", "Losses", "Spread"
+        )?;
+        for (i, s) in self.standings().iter().enumerate() {
+            writeln!(
+                f,
+                "{:>4} {:20} {:>6} {:>5} {:>6} {:>+7}",
+                i + 1,
+                s.name,
+                s.played,
+                s.wins,
+                s.losses,
+                s.spread
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entrants(n: usize) -> Vec<Entrant> {
+        (0..n)
+            .map(|i| Entrant {
+                name: format!("Player {}", i + 1),
+                bot: None,
+                engine: None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_round_robin() {
+        let mut tournament = Tournament::new("Club", Format::RoundRobin, entrants(5)).unwrap();
+        assert_eq!(tournament.total_rounds(), 5);
+        let mut met = HashSet::new();
+        while !tournament.is_finished() {
+            let round = tournament.rounds.len();
+            let pairings = tournament.pair_next_round().unwrap().to_vec();
+            assert_eq!(pairings.iter().filter(|p| p.second.is_none()).count(), 1);
+            for (i, p) in pairings.iter().enumerate() {
+                if let Some(second) = p.second {
+                    assert!(met.insert((p.first.min(second), p.first.max(second))));
+                    let score = GameScore {
+                        first: 400,
+                        second: 300 + 10 * p.first as TScore,
+                        game_file: None,
+                    };
+                    tournament.record_result(round, i, score).unwrap();
+                }
+            }
+        }
+        assert_eq!(met.len(), 10);
+        let standings = tournament.standings();
+        assert!(standings.iter().all(|s| s.played == 5));
+        assert!(standings.iter().all(|s| s.firsts == 2));
+        assert!(standings.windows(2).all(|w| w[0].wins >= w[1].wins));
+        assert_eq!(tournament.standings_csv().lines().count(), 6);
+    }
+
+    #[test]
+    fn test_swiss() {
+        let format = Format::Swiss { rounds: 3 };
+        let mut tournament = Tournament::new("Club", format, entrants(6)).unwrap();
+        tournament.pair_next_round().unwrap();
+        assert!(tournament.pair_next_round().is_err());
+        for i in 0..3 {
+            let score = GameScore {
+                first: 400,
+                second: 350,
+                game_file: None,
+            };
+            tournament.record_result(0, i, score).unwrap();
+        }
+        let round = tournament.pair_next_round().unwrap().to_vec();
+        // the winners meet each other
+        let winners: Vec<usize> = tournament.rounds[0].iter().map(|p| p.first).collect();
+        assert!(winners.contains(&round[0].first) && winners.contains(&round[0].second.unwrap()));
+        // nobody meets the same opponent again, whoever goes first
+        assert!(round.iter().all(|p| !tournament.rounds[0].iter().any(|q| {
+            (q.first == p.first && q.second == p.second)
+                || (Some(q.first) == p.second && q.second == Some(p.first))
+        })));
+    }
+}
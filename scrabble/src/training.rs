@@ -0,0 +1,505 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use chrono::Datelike;
+use rand::seq::SliceRandom;
+
+use crate::board::ScrabbleVariant;
+use crate::tiles::{Letter, ALPHABET};
+use crate::word_list::{anagrams, hook_letters, ALPHAGRAMS, WORDS};
+
+pub const DEFAULT_PROGRESS_FILE: &str = "training_progress.txt";
+
+// days until a card is next asked, indexed by its Leitner box
+const REVIEW_INTERVAL_DAYS: [i32; 6] = [0, 1, 3, 7, 14, 30];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum QuizKind {
+    Anagram,
+    Bingo7,
+    Bingo8,
+    Hooks,
+}
+
+impl QuizKind {
+    fn code(&self) -> &'static str {
+        match self {
+            QuizKind::Anagram => "anagram",
+            QuizKind::Bingo7 => "bingo7",
+            QuizKind::Bingo8 => "bingo8",
+            QuizKind::Hooks => "hooks",
+        }
+    }
+}
+
+impl Display for QuizKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QuizKind::Anagram => write!(f, "Anagrams"),
+            QuizKind::Bingo7 => write!(f, "Bingos from the rack"),
+            QuizKind::Bingo8 => write!(f, "Bingos through a letter on the board"),
+            QuizKind::Hooks => write!(f, "Front and back hooks"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuizSpec {
+    pub kind: QuizKind,
+    // word length for anagram and hook quizzes, the bingo drills always use 7 letter racks
+    pub length: usize,
+    // inclusive range of probability ranks, 1 being the most likely to be drawn
+    pub band: Option<(usize, usize)>,
+    pub questions: usize,
+    pub time_limit: Option<Duration>,
+}
+
+/*
+The number of ways the letters can be drawn from a full bag.  Ordering alphagrams by this gives
+the usual probability order used for word study, blanks are ignored.
+*/
+pub fn combinations(letters: &str, variant: &ScrabbleVariant) -> f64 {
+    let mut counts = [0u8; 26];
+    for c in letters.chars() {
+        counts[Letter::from(c).as_usize()] += 1;
+    }
+    counts
+        .iter()
+        .enumerate()
+        .map(|(i, &k)| binomial(variant.letter_distribution[i], k))
+        .product()
+}
+
+fn binomial(n: u8, k: u8) -> f64 {
+    if k > n {
+        return 0.0;
+    }
+    (0..k).fold(1.0, |acc, i| acc * (n - i) as f64 / (i + 1) as f64)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Question {
+    pub kind: QuizKind,
+    pub prompt: String,
+    pub answers: Vec<String>,
+}
+
+impl Question {
+    pub fn new(kind: QuizKind, prompt: &str) -> Self {
+        let mut answers: Vec<String> = match kind {
+            QuizKind::Anagram | QuizKind::Bingo7 => {
+                anagrams(prompt).iter().map(|w| w.to_string()).collect()
+            }
+            QuizKind::Bingo8 => ALPHABET
+                .iter()
+                .flat_map(|l| anagrams(&format!("{}{}", prompt, l)).iter())
+                .map(|w| w.to_string())
+                .collect(),
+            QuizKind::Hooks => {
+                let front = hook_letters("", prompt)
+                    .into_iter()
+                    .map(|l| format!("{}{}", l, prompt));
+                let back = hook_letters(prompt, "")
+                    .into_iter()
+                    .map(|l| format!("{}{}", prompt, l));
+                front.chain(back).collect()
+            }
+        };
+        answers.sort();
+        answers.dedup();
+        Self {
+            kind,
+            prompt: prompt.to_string(),
+            answers,
+        }
+    }
+
+    // the key used to track the question in the progress file
+    pub fn key(&self) -> String {
+        question_key(self.kind, &self.prompt)
+    }
+
+    /*
+    Hooks are answered as front and back letters separated by a slash, e.g. "BCF/S",
+    everything else as a list of words.
+    */
+    pub fn mark(&self, response: &str, elapsed: Duration, time_limit: Option<Duration>) -> Outcome {
+        let response = response.trim().to_uppercase();
+        let given: HashSet<String> = match (self.kind, response.split_once('/')) {
+            (QuizKind::Hooks, Some((front, back))) => front
+                .chars()
+                .filter(|c| c.is_ascii_uppercase())
+                .map(|c| format!("{}{}", c, self.prompt))
+                .chain(
+                    back.chars()
+                        .filter(|c| c.is_ascii_uppercase())
+                        .map(|c| format!("{}{}", self.prompt, c)),
+                )
+                .collect(),
+            _ => response
+                .split(|c: char| c.is_whitespace() || c == ',')
+                .filter(|w| !w.is_empty())
+                .map(|w| w.to_string())
+                .collect(),
+        };
+
+        let mut outcome = Outcome {
+            correct: vec![],
+            missed: vec![],
+            wrong: vec![],
+            elapsed,
+            timed_out: time_limit.is_some_and(|limit| elapsed > limit),
+        };
+        for answer in self.answers.iter() {
+            if given.contains(answer) {
+                outcome.correct.push(answer.clone());
+            } else {
+                outcome.missed.push(answer.clone());
+            }
+        }
+        outcome.wrong = given
+            .into_iter()
+            .filter(|w| !self.answers.contains(w))
+            .collect();
+        outcome.wrong.sort();
+        outcome
+    }
+}
+
+impl Display for Question {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.kind {
+            QuizKind::Anagram => write!(f, "Anagram {}", self.prompt),
+            QuizKind::Bingo7 => write!(f, "Find the bingos in {}", self.prompt),
+            QuizKind::Bingo8 => write!(f, "Find the 8 letter bingos in {} + ?", self.prompt),
+            QuizKind::Hooks => write!(f, "Hooks for {} (front/back)", self.prompt),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    pub correct: Vec<String>,
+    pub missed: Vec<String>,
+    pub wrong: Vec<String>,
+    pub elapsed: Duration,
+    pub timed_out: bool,
+}
+
+impl Outcome {
+    pub fn is_correct(&self) -> bool {
+        self.missed.is_empty() && self.wrong.is_empty() && !self.timed_out
+    }
+
+    // a point for every answer found and one off for every wrong guess, nothing if out of time
+    pub fn points(&self) -> i32 {
+        if self.timed_out {
+            0
+        } else {
+            (self.correct.len() as i32 - self.wrong.len() as i32).max(0)
+        }
+    }
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.timed_out {
+            writeln!(f, "Out of time!")?;
+        }
+        writeln!(f, "Found:  {}", self.correct.join(" "))?;
+        if !self.missed.is_empty() {
+            writeln!(f, "Missed: {}", self.missed.join(" "))?;
+        }
+        if !self.wrong.is_empty() {
+            writeln!(f, "Wrong:  {}", self.wrong.join(" "))?;
+        }
+        write!(
+            f,
+            "{} points in {:.1} seconds",
+            self.points(),
+            self.elapsed.as_secs_f64()
+        )
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct QuizSummary {
+    pub questions: usize,
+    pub correct: usize,
+    pub points: i32,
+    pub time: Duration,
+}
+
+impl QuizSummary {
+    pub fn add(&mut self, outcome: &Outcome) {
+        self.questions += 1;
+        if outcome.is_correct() {
+            self.correct += 1;
+        }
+        self.points += outcome.points();
+        self.time += outcome.elapsed;
+    }
+}
+
+impl Display for QuizSummary {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} of {} questions correct, {} points, {:.1} seconds per question",
+            self.correct,
+            self.questions,
+            self.points,
+            if self.questions == 0 {
+                0.0
+            } else {
+                self.time.as_secs_f64() / self.questions as f64
+            }
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Card {
+    pub level: usize,
+    pub due: i32,
+    pub attempts: u32,
+    pub correct: u32,
+}
+
+// Leitner style spaced repetition, stored as a tab separated text file
+#[derive(Debug, Clone)]
+pub struct Progress {
+    path: PathBuf,
+    pub cards: HashMap<String, Card>,
+}
+
+pub fn today() -> i32 {
+    chrono::Local::now().date_naive().num_days_from_ce()
+}
+
+impl Progress {
+    pub fn load(path: &Path) -> io::Result<Progress> {
+        let mut cards = HashMap::new();
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e),
+        };
+        for line in contents.lines() {
+            let fields: Vec<&str> = line.split('\t').collect();
+            if let [key, level, due, attempts, correct] = fields[..] {
+                let card = Card {
+                    level: level.parse().unwrap_or(0),
+                    due: due.parse().unwrap_or(0),
+                    attempts: attempts.parse().unwrap_or(0),
+                    correct: correct.parse().unwrap_or(0),
+                };
+                cards.insert(key.to_string(), card);
+            }
+        }
+        Ok(Progress {
+            path: path.to_path_buf(),
+            cards,
+        })
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let mut keys: Vec<&String> = self.cards.keys().collect();
+        keys.sort();
+        let mut contents = String::new();
+        for key in keys {
+            let card = self.cards[key];
+            contents.push_str(&format!(
+                "{}\t{}\t{}\t{}\t{}\n",
+                key, card.level, card.due, card.attempts, card.correct
+            ));
+        }
+        fs::write(&self.path, contents)
+    }
+
+    // a right answer moves the card up a box, a wrong one sends it back to the start
+    pub fn record(&mut self, key: &str, correct: bool, today: i32) {
+        let card = self.cards.entry(key.to_string()).or_insert(Card {
+            level: 0,
+            due: today,
+            attempts: 0,
+            correct: 0,
+        });
+        card.attempts += 1;
+        if correct {
+            card.correct += 1;
+            card.level = (card.level + 1).min(REVIEW_INTERVAL_DAYS.len() - 1);
+        } else {
+            card.level = 0;
+        }
+        card.due = today + REVIEW_INTERVAL_DAYS[card.level];
+    }
+
+    pub fn is_due(&self, key: &str, today: i32) -> bool {
+        self.cards.get(key).is_none_or(|card| card.due <= today)
+    }
+}
+
+// the prompts for a quiz, most probable first
+fn ranked_prompts(kind: QuizKind, length: usize, variant: &ScrabbleVariant) -> Vec<String> {
+    let mut prompts: Vec<String> = match kind {
+        QuizKind::Anagram => ALPHAGRAMS
+            .keys()
+            .filter(|a| a.len() == length)
+            .cloned()
+            .collect(),
+        QuizKind::Bingo7 => ALPHAGRAMS
+            .keys()
+            .filter(|a| a.len() == 7)
+            .cloned()
+            .collect(),
+        QuizKind::Bingo8 => {
+            // every 7 letter rack that makes an 8 letter word with one more letter
+            let mut stems = HashSet::new();
+            for a in ALPHAGRAMS.keys().filter(|a| a.len() == 8) {
+                for i in 0..8 {
+                    stems.insert(format!("{}{}", &a[..i], &a[i + 1..]));
+                }
+            }
+            stems.into_iter().collect()
+        }
+        QuizKind::Hooks => WORDS
+            .iter()
+            .filter(|w| w.len() == length)
+            .map(|w| w.to_string())
+            .collect(),
+    };
+    let mut ranked: Vec<(f64, String)> = prompts
+        .drain(..)
+        .map(|p| (combinations(&p, variant), p))
+        .collect();
+    ranked.sort_by(|a, b| b.0.total_cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+    ranked.into_iter().map(|(_, p)| p).collect()
+}
+
+/*
+Questions in the band which are due for review come first, oldest first, then new questions
+in random order.  Questions seen before but not yet due are left out.  A band the wrong way
+round has nothing in it.
+*/
+pub fn build_quiz(
+    spec: &QuizSpec,
+    variant: &ScrabbleVariant,
+    progress: &Progress,
+    today: i32,
+) -> Vec<Question> {
+    let prompts = ranked_prompts(spec.kind, spec.length, variant);
+    let (from, to) = spec.band.unwrap_or((1, prompts.len()));
+    let start = (from.max(1) - 1).min(prompts.len());
+    let band = &prompts[start..to.clamp(start, prompts.len())];
+
+    let mut due: Vec<(i32, &String)> = vec![];
+    let mut new: Vec<&String> = vec![];
+    for prompt in band {
+        match progress.cards.get(&question_key(spec.kind, prompt)) {
+            Some(card) if card.due <= today => due.push((card.due, prompt)),
+            Some(_) => {}
+            None => new.push(prompt),
+        }
+    }
+    due.sort();
+    new.shuffle(&mut rand::thread_rng());
+
+    due.into_iter()
+        .map(|(_, p)| p)
+        .chain(new)
+        .take(spec.questions)
+        .map(|p| Question::new(spec.kind, p))
+        .collect()
+}
+
+fn question_key(kind: QuizKind, prompt: &str) -> String {
+    format!("{}:{}", kind.code(), prompt)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::SCRABBLE_VARIANT_OFFICIAL;
+
+    #[test]
+    fn test_combinations() {
+        // 9 As and 12 Es in the bag, against a single Q and Z
+        assert_eq!(combinations("AE", &SCRABBLE_VARIANT_OFFICIAL), 108.0);
+        assert_eq!(combinations("QZ", &SCRABBLE_VARIANT_OFFICIAL), 1.0);
+        assert_eq!(combinations("ZZ", &SCRABBLE_VARIANT_OFFICIAL), 0.0);
+    }
+
+    #[test]
+    fn test_anagram_question() {
+        let question = Question::new(QuizKind::Bingo7, "AEINRST");
+        assert!(question.answers.contains(&"RETAINS".to_string()));
+        assert!(question.answers.contains(&"STAINER".to_string()));
+
+        let outcome = question.mark("retains nastier", Duration::from_secs(5), None);
+        assert!(outcome.correct.contains(&"RETAINS".to_string()));
+        assert!(!outcome.missed.is_empty());
+        assert!(!outcome.is_correct());
+    }
+
+    #[test]
+    fn test_hooks_question() {
+        let question = Question::new(QuizKind::Hooks, "ZA");
+        let outcome = question.mark(
+            "/GPSX",
+            Duration::from_secs(1),
+            Some(Duration::from_secs(2)),
+        );
+        assert_eq!(outcome.correct, vec!["ZAG", "ZAP", "ZAS", "ZAX"]);
+        assert!(outcome.wrong.is_empty());
+
+        let outcome = question.mark(
+            "/GPSX",
+            Duration::from_secs(3),
+            Some(Duration::from_secs(2)),
+        );
+        assert!(outcome.timed_out);
+        assert_eq!(outcome.points(), 0);
+    }
+
+    #[test]
+    fn test_build_quiz() {
+        let progress = Progress {
+            path: PathBuf::new(),
+            cards: HashMap::new(),
+        };
+        let mut spec = QuizSpec {
+            kind: QuizKind::Hooks,
+            length: 2,
+            band: Some((1, 5)),
+            questions: 10,
+            time_limit: None,
+        };
+        assert_eq!(
+            build_quiz(&spec, &SCRABBLE_VARIANT_OFFICIAL, &progress, 0).len(),
+            5
+        );
+        spec.band = Some((10, 5));
+        assert!(build_quiz(&spec, &SCRABBLE_VARIANT_OFFICIAL, &progress, 0).is_empty());
+    }
+
+    #[test]
+    fn test_progress() {
+        let mut progress = Progress {
+            path: PathBuf::new(),
+            cards: HashMap::new(),
+        };
+        assert!(progress.is_due("anagram:AEINRST", 100));
+        progress.record("anagram:AEINRST", true, 100);
+        assert!(!progress.is_due("anagram:AEINRST", 100));
+        assert!(progress.is_due("anagram:AEINRST", 101));
+        progress.record("anagram:AEINRST", true, 101);
+        assert_eq!(progress.cards["anagram:AEINRST"].due, 104);
+        progress.record("anagram:AEINRST", false, 104);
+        assert_eq!(progress.cards["anagram:AEINRST"].level, 0);
+    }
+}
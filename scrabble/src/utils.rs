@@ -1,6 +1,7 @@
 use regex::Regex;
 use std::str::FromStr;
-use std::time::{Duration, Instant};
+use std::time::Duration;
+use web_time::Instant;
 
 pub fn get_numbers<T: FromStr>(source: &str) -> Vec<T>
 where
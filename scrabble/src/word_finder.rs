@@ -0,0 +1,212 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use regex::Regex;
+
+use crate::board::ScrabbleVariant;
+use crate::tiles::Letter;
+use crate::word_list::{tag_word, WORDS};
+
+/*
+A word search.  Any combination of the parts can be given, a word has to satisfy all of them.
+    pattern     ? is any letter, * any run of letters and [..] one of a set of letters, e.g. ?A?E, *ING, [AEIOU]Z*
+    anagram     uses exactly these tiles, ? being a blank, e.g. AEIRST?
+    contains    includes at least these letters
+    length      between the minimum and maximum lengths
+*/
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WordQuery {
+    pub pattern: Option<String>,
+    pub anagram: Option<String>,
+    pub contains: Option<String>,
+    pub min_length: Option<usize>,
+    pub max_length: Option<usize>,
+}
+
+// expecting space separated terms, e.g. "*ING length=5-8" or "anagram=AEIRST? contains=Q"
+impl FromStr for WordQuery {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut query = WordQuery::default();
+        for term in s.to_uppercase().split_whitespace() {
+            match term.split_once('=') {
+                Some(("ANAGRAM", letters)) => query.anagram = Some(letters.to_string()),
+                Some(("CONTAINS", letters)) => query.contains = Some(letters.to_string()),
+                Some(("LENGTH", range)) => {
+                    let (min, max) = range.split_once('-').unwrap_or((range, range));
+                    let parse = |n: &str| {
+                        n.parse::<usize>()
+                            .map_err(|_| format!("Invalid length: {}", range))
+                    };
+                    query.min_length = Some(parse(min)?);
+                    query.max_length = Some(parse(max)?);
+                }
+                Some(_) => return Err(format!("Invalid search term: {}", term)),
+                None => query.pattern = Some(term.to_string()),
+            }
+        }
+        if query == WordQuery::default() {
+            return Err("Nothing to search for".to_string());
+        }
+        Ok(query)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct WordMatch {
+    pub word: String,
+    pub score: u16,
+}
+
+impl Display for WordMatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:16} {:3}", tag_word(&self.word), self.score)
+    }
+}
+
+fn pattern_to_regex(pattern: &str) -> Result<Regex, String> {
+    let mut re = String::from("^");
+    let mut in_set = false;
+    for c in pattern.chars() {
+        match c {
+            '?' if !in_set => re.push_str("[A-Z]"),
+            '*' if !in_set => re.push_str("[A-Z]*"),
+            '[' if !in_set => {
+                in_set = true;
+                re.push('[');
+            }
+            ']' if in_set => {
+                in_set = false;
+                re.push(']');
+            }
+            'A'..='Z' => re.push(c),
+            _ => return Err(format!("Invalid pattern: {}", pattern)),
+        }
+    }
+    if in_set {
+        return Err(format!("Invalid pattern: {}", pattern));
+    }
+    re.push('$');
+    Regex::new(&re).map_err(|_| format!("Invalid pattern: {}", pattern))
+}
+
+fn letter_counts(letters: &str) -> ([u8; 26], u8) {
+    let mut counts = [0u8; 26];
+    let mut blanks = 0u8;
+    for c in letters.chars() {
+        match c {
+            'A'..='Z' => counts[Letter::from(c).as_usize()] += 1,
+            _ => blanks += 1,
+        }
+    }
+    (counts, blanks)
+}
+
+/*
+Returns the matching words, highest scoring first.  For an anagram search any letters
+which have to be made with a blank score nothing.
+*/
+pub fn find_words(
+    query: &WordQuery,
+    scrabble_variant: &ScrabbleVariant,
+) -> Result<Vec<WordMatch>, String> {
+    let pattern = match &query.pattern {
+        Some(p) => Some(pattern_to_regex(p)?),
+        None => None,
+    };
+    let anagram = query.anagram.as_deref().map(letter_counts);
+    let contains = query.contains.as_deref().map(|c| letter_counts(c).0);
+
+    let mut result = vec![];
+    for word in WORDS.iter() {
+        if query.min_length.is_some_and(|min| word.len() < min)
+            || query.max_length.is_some_and(|max| word.len() > max)
+        {
+            continue;
+        }
+        if let Some(re) = &pattern {
+            if !re.is_match(word) {
+                continue;
+            }
+        }
+        let (counts, _) = letter_counts(word);
+        if let Some(contains) = contains {
+            if (0..26).any(|i| counts[i] < contains[i]) {
+                continue;
+            }
+        }
+        // the tiles scored at face value, which is all of them unless blanks are involved
+        let mut scored = counts;
+        if let Some((rack, blanks)) = anagram {
+            if word.len() != rack.iter().sum::<u8>() as usize + blanks as usize {
+                continue;
+            }
+            let blanks_needed: u8 = (0..26).map(|i| counts[i].saturating_sub(rack[i])).sum();
+            if blanks_needed > blanks {
+                continue;
+            }
+            scored = std::array::from_fn(|i| counts[i].min(rack[i]));
+        }
+        let score = scored
+            .iter()
+            .zip(scrabble_variant.letter_values.iter())
+            .map(|(&n, &value)| (n * value) as u16)
+            .sum();
+        result.push(WordMatch {
+            word: word.to_string(),
+            score,
+        });
+    }
+    result.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.word.cmp(&b.word)));
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::SCRABBLE_VARIANT_OFFICIAL;
+
+    fn words(query: &str) -> Vec<String> {
+        let query = WordQuery::from_str(query).unwrap();
+        find_words(&query, &SCRABBLE_VARIANT_OFFICIAL)
+            .unwrap()
+            .into_iter()
+            .map(|m| m.word)
+            .collect()
+    }
+
+    #[test]
+    fn test_patterns() {
+        let found = words("?A?E");
+        assert!(found.contains(&"CAKE".to_string()));
+        assert!(found.iter().all(|w| w.len() == 4));
+
+        let found = words("[AEIOU]Z* length=2-4");
+        assert!(found.contains(&"AZO".to_string()));
+        assert!(found
+            .iter()
+            .all(|w| w.len() <= 4 && w.chars().nth(1) == Some('Z')));
+
+        assert!(WordQuery::from_str("[AE").is_ok());
+        let query = WordQuery::from_str("[AE").unwrap();
+        assert!(find_words(&query, &SCRABBLE_VARIANT_OFFICIAL).is_err());
+    }
+
+    #[test]
+    fn test_anagram_with_blank() {
+        let query = WordQuery::from_str("anagram=QI?").unwrap();
+        let found = find_words(&query, &SCRABBLE_VARIANT_OFFICIAL).unwrap();
+        // QADI is out, it has four letters
+        assert!(found.iter().all(|m| m.word.len() == 3));
+        // the blank in QIS scores nothing
+        let qis = found.iter().find(|m| m.word == "QIS").unwrap();
+        assert_eq!(qis.score, 11);
+        assert!(found.windows(2).all(|w| w[0].score >= w[1].score));
+    }
+
+    #[test]
+    fn test_contains() {
+        let found = words("contains=QZ length=2-6");
+        assert!(found.iter().all(|w| w.contains('Q') && w.contains('Z')));
+    }
+}
@@ -1,12 +1,314 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::OnceLock;
+
 use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+
+use crate::tiles::{LetterSet, ALPHABET};
 
 const WORD_FILE: &'static str = include_str!("sowpods.txt");
 
 lazy_static! {
     pub static ref WORDS: std::collections::HashSet<&'static str> =
         WORD_FILE.split_whitespace().collect();
+    // words keyed by their alphagram, i.e. their letters in alphabetical order
+    pub static ref ALPHAGRAMS: HashMap<String, Vec<&'static str>> = {
+        let mut alphagrams: HashMap<String, Vec<&'static str>> = HashMap::new();
+        for word in WORD_FILE.split_whitespace() {
+            alphagrams.entry(alphagram(word)).or_default().push(word);
+        }
+        alphagrams
+    };
 }
 
 pub fn is_word(word: &str) -> bool {
     WORDS.contains(word)
 }
+
+pub fn alphagram(word: &str) -> String {
+    let mut letters: Vec<char> = word.chars().collect();
+    letters.sort_unstable();
+    letters.into_iter().collect()
+}
+
+// all the words which use exactly the given letters
+pub fn anagrams(letters: &str) -> &'static [&'static str] {
+    match ALPHAGRAMS.get(&alphagram(letters)) {
+        Some(words) => words,
+        None => &[],
+    }
+}
+
+/*
+The letters which can fill the gap between two words, so that together they form a single word.
+Either word can be empty, so front hooks are hook_letters("", word) and back hooks are hook_letters(word, "").
+*/
+pub fn hook_letters(before: &str, after: &str) -> LetterSet {
+    WordCheck::Exact.hook_letters(before, after)
+}
+
+// how the letters played on the board are checked against the word list
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WordCheck {
+    #[default]
+    Exact,
+    // any arrangement of the letters of a word is accepted
+    Clabbers,
+}
+
+impl WordCheck {
+    pub fn is_valid(&self, word: &str) -> bool {
+        match self {
+            WordCheck::Exact => is_word(word),
+            WordCheck::Clabbers => ALPHAGRAMS.contains_key(&alphagram(word)),
+        }
+    }
+
+    pub fn hook_letters(&self, before: &str, after: &str) -> LetterSet {
+        let mut ls = LetterSet::new_empty();
+        for l in ALPHABET {
+            if self.is_valid(format!("{}{}{}", before, l, after).as_str()) {
+                ls.add(*l);
+            }
+        }
+        ls
+    }
+}
+
+impl Display for WordCheck {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WordCheck::Exact => write!(f, "Standard"),
+            WordCheck::Clabbers => write!(f, "Clabbers"),
+        }
+    }
+}
+
+pub const BUILTIN_LEXICON_NAME: &str = "SOWPODS";
+pub const DEFAULT_COMPARISON_LEXICON_FILE: &str = "twl.txt";
+
+static COMPARISON_LEXICON: OnceLock<Lexicon> = OnceLock::new();
+
+// a word list loaded at run time, one word per line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lexicon {
+    pub name: String,
+    words: HashSet<String>,
+}
+
+impl Lexicon {
+    pub fn parse(name: &str, contents: &str) -> Lexicon {
+        Lexicon {
+            name: name.to_string(),
+            words: contents
+                .split_whitespace()
+                .map(|w| w.to_uppercase())
+                .collect(),
+        }
+    }
+
+    // named after the file, e.g. twl.txt is TWL
+    pub fn load(path: &Path) -> io::Result<Lexicon> {
+        let name = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_uppercase())
+            .unwrap_or_default();
+        Ok(Self::parse(&name, &fs::read_to_string(path)?))
+    }
+
+    // the word list the game is played with
+    pub fn builtin() -> Lexicon {
+        Lexicon {
+            name: BUILTIN_LEXICON_NAME.to_string(),
+            words: WORDS.iter().map(|w| w.to_string()).collect(),
+        }
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        self.words.contains(word)
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WordStatus {
+    Both,
+    PrimaryOnly,
+    OtherOnly,
+    Neither,
+}
+
+impl WordStatus {
+    pub fn of(word: &str, primary: &Lexicon, other: &Lexicon) -> WordStatus {
+        match (primary.contains(word), other.contains(word)) {
+            (true, true) => WordStatus::Both,
+            (true, false) => WordStatus::PrimaryOnly,
+            (false, true) => WordStatus::OtherOnly,
+            (false, false) => WordStatus::Neither,
+        }
+    }
+
+    // the usual markers, e.g. # for a word which is only valid in Collins
+    pub fn tag(&self) -> &'static str {
+        match self {
+            WordStatus::Both | WordStatus::Neither => "",
+            WordStatus::PrimaryOnly => "#",
+            WordStatus::OtherOnly => "$",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LexiconDiff {
+    pub primary: String,
+    pub other: String,
+    pub common: usize,
+    pub primary_only: Vec<String>,
+    pub other_only: Vec<String>,
+}
+
+// the words in one list but not the other, shortest first
+pub fn compare_lexicons(primary: &Lexicon, other: &Lexicon) -> LexiconDiff {
+    let sorted = |mut words: Vec<String>| {
+        words.sort_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)));
+        words
+    };
+    LexiconDiff {
+        primary: primary.name.clone(),
+        other: other.name.clone(),
+        common: primary.words.intersection(&other.words).count(),
+        primary_only: sorted(primary.words.difference(&other.words).cloned().collect()),
+        other_only: sorted(other.words.difference(&primary.words).cloned().collect()),
+    }
+}
+
+impl Display for LexiconDiff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{} words in both lists", self.common)?;
+        writeln!(
+            f,
+            "{} words only in {} (tagged {})",
+            self.primary_only.len(),
+            self.primary,
+            WordStatus::PrimaryOnly.tag()
+        )?;
+        write!(
+            f,
+            "{} words only in {} (tagged {})",
+            self.other_only.len(),
+            self.other,
+            WordStatus::OtherOnly.tag()
+        )
+    }
+}
+
+// sets the list the game's words are compared against when tagging, this can only be done once
+pub fn load_comparison_lexicon(path: &Path) -> io::Result<&'static Lexicon> {
+    let lexicon = Lexicon::load(path)?;
+    COMPARISON_LEXICON.set(lexicon).map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            "comparison word list already loaded",
+        )
+    })?;
+    Ok(COMPARISON_LEXICON.get().unwrap())
+}
+
+pub fn comparison_lexicon() -> Option<&'static Lexicon> {
+    COMPARISON_LEXICON.get()
+}
+
+// the word followed by its tag, untagged if no comparison list has been loaded
+// the word is shown as it is given, blanks in lower case and all
+pub fn tag_word(word: &str) -> String {
+    let upper = word.to_uppercase();
+    let tag = match comparison_lexicon() {
+        Some(other) => match (is_word(&upper), other.contains(&upper)) {
+            (true, false) => WordStatus::PrimaryOnly.tag(),
+            (false, true) => WordStatus::OtherOnly.tag(),
+            _ => "",
+        },
+        None => "",
+    };
+    format!("{}{}", word, tag)
+}
+
+pub const DEFAULT_FREQUENCY_FILE: &str = "word_frequency.txt";
+
+static WORD_FREQUENCY: OnceLock<HashMap<String, usize>> = OnceLock::new();
+
+/*
+Loads a word frequency list, most common word first.  Only the first field of each line is used,
+so a file of words with their counts will do.  This can only be done once.
+*/
+pub fn load_frequency_list(path: &Path) -> io::Result<usize> {
+    let contents = fs::read_to_string(path)?;
+    let mut ranks = HashMap::new();
+    for word in contents
+        .lines()
+        .filter_map(|line| line.split_whitespace().next())
+    {
+        let rank = ranks.len() + 1;
+        ranks.entry(word.to_uppercase()).or_insert(rank);
+    }
+    let count = ranks.len();
+    WORD_FREQUENCY.set(ranks).map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            "frequency list already loaded",
+        )
+    })?;
+    Ok(count)
+}
+
+pub fn has_frequency_list() -> bool {
+    WORD_FREQUENCY.get().is_some()
+}
+
+// 1 for the most common word, None if it isn't in the frequency list
+pub fn frequency_rank(word: &str) -> Option<usize> {
+    WORD_FREQUENCY
+        .get()
+        .and_then(|ranks| ranks.get(word).copied())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare_lexicons() {
+        let collins = Lexicon::parse("COLLINS", "QI ZA CAT\nZO");
+        let twl = Lexicon::parse("TWL", "qi za cat\nOK");
+        let diff = compare_lexicons(&collins, &twl);
+        assert_eq!(diff.common, 3);
+        assert_eq!(diff.primary_only, vec!["ZO"]);
+        assert_eq!(diff.other_only, vec!["OK"]);
+        assert_eq!(WordStatus::of("ZO", &collins, &twl).tag(), "#");
+        assert_eq!(WordStatus::of("OK", &collins, &twl).tag(), "$");
+        assert_eq!(WordStatus::of("QI", &collins, &twl).tag(), "");
+    }
+
+    #[test]
+    fn test_clabbers() {
+        assert!(!WordCheck::Exact.is_valid("TCA"));
+        assert!(WordCheck::Clabbers.is_valid("TCA"));
+        assert!(!WordCheck::Clabbers.is_valid("QQ"));
+        // ZA and AZ are both anagrams of a word, so Z is a front and back hook to A
+        let hooks = WordCheck::Clabbers.hook_letters("", "A");
+        assert!(hooks.contains('Z'.into()));
+        assert!(!WordCheck::Exact.hook_letters("A", "").contains('Z'.into()));
+    }
+}
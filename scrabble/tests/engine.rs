@@ -0,0 +1,29 @@
+use scrabble::board::SCRABBLE_VARIANT_OFFICIAL;
+use scrabble::notation::read_position;
+use scrabble::protocol::ExternalStrategy;
+use scrabble::strategy::{Action, Strategy};
+use scrabble::word_list::WordCheck;
+
+// this crate's own engine command, run as another program would be
+#[test]
+fn test_external_engine() {
+    let position = "15/15/15/15/15/15/15/15/15/15/15/15/15/15/15 AEINRST/7 0/0 1";
+    let game = read_position(position, &SCRABBLE_VARIANT_OFFICIAL, WordCheck::Exact).unwrap();
+
+    let engine = ExternalStrategy::new(&format!("{} engine", env!("CARGO_BIN_EXE_scrabble")));
+    for _ in 0..2 {
+        match engine.choose_action(&game.view()) {
+            Action::Play(game_move) => assert_eq!(game_move.tiles.len(), 7),
+            action => panic!("expected a play, got {:?}", action),
+        }
+    }
+    assert!(engine.take_errors().is_empty());
+
+    // a program which can't be started passes, and says why
+    let missing = ExternalStrategy::new("scrabble-engine-that-does-not-exist");
+    assert_eq!(missing.choose_action(&game.view()), Action::Pass);
+    let errors = missing.take_errors();
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].starts_with("Could not start"));
+    assert!(missing.take_errors().is_empty());
+}